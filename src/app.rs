@@ -1,16 +1,27 @@
 use std::{
     cell::{RefCell, RefMut},
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    ops::{Bound, RangeInclusive},
     path::PathBuf,
+    time::Instant,
 };
 
-use chrono::Local;
+use chrono::{Local, Months, NaiveDate, TimeDelta};
 use crokey::Combiner;
-use ratatui::widgets::TableState;
+use ratatui::{layout::Rect, widgets::TableState};
 use tui_input::Input;
 
 use crate::{
-    config::Config,
-    todo::{Content, TodoItem, TodoList},
+    config::{Config, UrgencyCoefficients, WordLogic, WordMatch},
+    stats::Statistics,
+    todo::{parsing::ItemParseError, Content, RecurringUnit, TodoItem, TodoList},
+    urgency::Urgency,
+    view::{
+        AgendaSection, BatchPrompt, BoardColumns, DateKind, DayCount, Group, Grouping, SortField,
+        SortItem, SortKey,
+    },
 };
 
 /// Application.
@@ -20,14 +31,138 @@ pub struct App {
     pub key_combiner: Combiner,
     /// Configuration
     pub config: Config,
+    /// Todo file path
+    pub todo_path: PathBuf,
     /// Archive path
     pub archive_path: Option<PathBuf>,
     /// Is the application running?
     pub running: bool,
+    /// Was quitting requested with unsaved changes, so that the next request quits?
+    pub quit_pending: bool,
     /// Sorted TodoList
     pub todo_list: SortedFilteredTodoList,
     /// Application state
     pub state: FocusState,
+    /// Project and context sidebar
+    pub sidebar: Sidebar,
+    /// Month calendar
+    pub calendar: Calendar,
+    /// Agenda of the coming days
+    pub agenda: AgendaView,
+    /// Board of the filtered items
+    pub board: BoardView,
+    /// Is the detail pane of the selected item shown?
+    pub details_visible: bool,
+    /// Areas of the last rendered frame
+    pub click_areas: ClickAreas,
+    /// Time and list row of the last mouse click
+    pub last_click: Option<(Instant, usize)>,
+    /// Transient message shown in the status bar
+    pub message: Option<StatusMessage>,
+    /// Reminders waiting for a text input to be left
+    pub pending_reminders: Vec<(TodoItem, DateKind)>,
+    /// Count typed before a list navigation key
+    pub count_prefix: Option<usize>,
+    /// Was the top key pressed once already?
+    pub top_pending: bool,
+    /// Is the compact layout used because the terminal is small?
+    pub compact: bool,
+}
+
+/// Short message shown in the status bar for a limited amount of ticks
+#[derive(Debug)]
+pub struct StatusMessage {
+    /// Message text
+    pub text: String,
+    /// Does the message report an error?
+    pub error: bool,
+    /// Remaining ticks until the message disappears
+    ticks_left: u32,
+}
+
+/// Screen areas of the last rendered frame, used to map mouse events
+#[derive(Debug, Default)]
+pub struct ClickAreas {
+    /// Completion filter indicator
+    pub completion_filter: Rect,
+    /// Priority filter indicator
+    pub priority_filter: Rect,
+    /// Threshold filter indicator
+    pub t_filter: Rect,
+    /// Visible list rows with their row index
+    pub list_rows: Vec<(usize, Rect)>,
+    /// The list including its border
+    pub list: Rect,
+}
+
+/// State of the sidebar listing projects and contexts
+#[derive(Debug, Default)]
+pub struct Sidebar {
+    /// Is the sidebar shown?
+    pub visible: bool,
+    /// Table state for the ui
+    table_state: RefCell<TableState>,
+}
+
+/// State of the month calendar of due and threshold dates
+#[derive(Debug)]
+pub struct Calendar {
+    /// Is the calendar shown?
+    pub visible: bool,
+    /// Day under the cursor, the list is filtered to its items while visible
+    pub day: NaiveDate,
+}
+
+impl Calendar {
+    /// Moves the cursor by the given amount of days.
+    pub fn move_days(&mut self, days: i64) {
+        self.day += TimeDelta::days(days);
+    }
+
+    /// Moves the cursor by the given amount of months, clamping the day to the month's length.
+    pub fn move_months(&mut self, months: i32) {
+        let delta = Months::new(months.unsigned_abs());
+        let day = if months < 0 {
+            self.day.checked_sub_months(delta)
+        } else {
+            self.day.checked_add_months(delta)
+        };
+        if let Some(day) = day {
+            self.day = day;
+        }
+    }
+}
+
+/// State of the agenda listing open items by day
+#[derive(Debug, Default)]
+pub struct AgendaView {
+    /// Table state for the ui
+    table_state: RefCell<TableState>,
+}
+
+/// A row of the agenda
+#[derive(Debug, Clone, Copy)]
+pub enum AgendaRow {
+    /// Heading of a section
+    Header(AgendaSection),
+    /// Item with the list index, listed because of the given date
+    Item { index: usize, kind: DateKind },
+}
+
+/// A project or context occurring in a TodoList
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Project(String),
+    Context(String),
+}
+
+/// Amount of items belonging to a [`Category`]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CategoryCount {
+    /// Incomplete items
+    pub open: usize,
+    /// All items
+    pub total: usize,
 }
 
 /// A wrapper allowing a sorted and filtered view of a TodoList
@@ -39,8 +174,60 @@ pub struct SortedFilteredTodoList {
     list_table_state: RefCell<TableState>,
     /// The current filter
     filter: TodoListFilter,
+    /// The current sort order, earlier keys taking precedence
+    sort: Vec<SortKey>,
     /// The list view consisting of indices into the underlying list
     view_indices: Vec<usize>,
+    /// The current grouping of the list view
+    grouping: Grouping,
+    /// Groups whose items are not shown
+    collapsed: BTreeSet<Group>,
+    /// The rows of the list view, selected through the table state
+    rows: Vec<ViewRow>,
+    /// The current search, highlighting items without hiding the others
+    search: Input,
+    /// Has the underlying list changed since it was last saved?
+    modified: bool,
+    /// The date the view was last computed for
+    today: NaiveDate,
+    /// The last date reminders were collected for
+    last_reminded: NaiveDate,
+    /// Weights used to compute the urgency of items
+    urgency: UrgencyCoefficients,
+    /// Marked items given by their index into the underlying list
+    marks: BTreeSet<usize>,
+    /// Items removed by archiving since the last save
+    archived: Vec<TodoItem>,
+    /// Previous states of the list and archived items, the most recent one last
+    history: Vec<(TodoList, Vec<TodoItem>)>,
+    /// Incremented with every change of the underlying list
+    revision: usize,
+}
+
+/// Maximum amount of remembered undo steps
+const UNDO_LIMIT: usize = 100;
+
+/// A row of the list view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViewRow {
+    /// The header of a group
+    Header {
+        group: Group,
+        /// Amount of items in the group
+        count: usize,
+        collapsed: bool,
+    },
+    /// An item given by its index into the underlying list
+    Item(usize),
+}
+
+/// State of the board showing the filtered items in columns
+#[derive(Debug, Default)]
+pub struct BoardView {
+    /// Focused column
+    pub column: usize,
+    /// Table states of the columns for the ui
+    column_states: RefCell<Vec<TableState>>,
 }
 
 /// Used to filter items in a TodoList
@@ -62,33 +249,183 @@ pub struct TodoListFilter {
     pub priority: Option<Option<char>>,
     /// Filtering items with threshold
     pub t: bool,
+    /// Filtering for due date
+    pub due: DueFilter,
+    /// Showing items marked as hidden with `h:1`
+    pub hidden: bool,
+    /// Filtering for items due or reaching their threshold on a day
+    ///
+    /// Overrides the threshold and due filter if set
+    pub day: Option<NaiveDate>,
+    /// Filtering for projects and contexts
+    ///
+    /// If not empty, items need to belong to at least one of the categories
+    pub categories: BTreeSet<Category>,
+    /// Whether all or any of the input words need to match
+    pub word_logic: WordLogic,
+    /// How input words are matched against the item content
+    pub word_match: WordMatch,
+}
+
+/// The due date buckets a [`TodoListFilter`] can filter for
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DueFilter {
+    /// Ignore due dates
+    #[default]
+    Any,
+    /// Items due before today
+    Overdue,
+    /// Items due today
+    Today,
+    /// Items due between today and the given amount of days from now
+    Within(u32),
+    /// Items without due date
+    NoDue,
 }
 
 /// State to track where the user focus is
 #[derive(Debug, Default)]
 pub enum FocusState {
     /// Editing the filter
-    FilterFocus {
-        previous_selection_index: usize,
-        previous_selection_item: TodoItem,
-    },
+    FilterFocus { previous_selection: Option<usize> },
     /// Browsing the list
     #[default]
     ListFocus,
+    /// Browsing the sidebar
+    SidebarFocus,
+    /// Editing the search
+    SearchFocus { previous_selection: Option<usize> },
+    /// Editing the sort order
+    SortFocus {
+        input_field: Input,
+        previous_sort: Vec<SortKey>,
+    },
+    /// Browsing the calendar
+    CalendarFocus,
+    /// Browsing the board
+    BoardFocus,
+    /// Browsing the agenda
+    AgendaFocus,
+    /// Viewing the statistics, per week if `weekly` is set
+    StatsFocus {
+        statistics: Statistics,
+        weekly: bool,
+    },
+    /// Extending the marks from the anchor item to the selection
+    VisualFocus {
+        anchor: usize,
+        base_marks: BTreeSet<usize>,
+    },
+    /// Entering the value of a batch operation
+    BatchFocus {
+        input_field: Input,
+        prompt: BatchPrompt,
+        indices: Vec<usize>,
+    },
+    /// Entering a new date for an item of the agenda
+    RescheduleFocus {
+        input_field: Input,
+        index: usize,
+        kind: DateKind,
+    },
+    /// Previewing pasted lines before adding them as new items
+    PasteFocus { lines: Vec<PastedLine>, scroll: u16 },
+    /// Reading the reminders of items that became due or reached their threshold date
+    ReminderFocus {
+        reminders: Vec<(TodoItem, DateKind)>,
+        previous_state: Box<FocusState>,
+        scroll: u16,
+    },
+    /// Reading the help overlay
+    HelpFocus {
+        previous_state: Box<FocusState>,
+        scroll: u16,
+    },
     /// Intermediate invalid state
     Invalid,
 }
 
+impl FocusState {
+    /// Returns the name of the mode shown in the status bar
+    pub fn name(&self) -> &'static str {
+        match self {
+            FocusState::FilterFocus { .. } => "FILTER",
+            FocusState::ListFocus => "LIST",
+            FocusState::SidebarFocus => "SIDEBAR",
+            FocusState::SearchFocus { .. } => "SEARCH",
+            FocusState::SortFocus { .. } => "SORT",
+            FocusState::CalendarFocus => "CALENDAR",
+            FocusState::VisualFocus { .. } => "VISUAL",
+            FocusState::BatchFocus { .. } => "BATCH",
+            FocusState::BoardFocus => "BOARD",
+            FocusState::AgendaFocus => "AGENDA",
+            FocusState::StatsFocus { .. } => "STATS",
+            FocusState::RescheduleFocus { .. } => "RESCHEDULE",
+            FocusState::PasteFocus { .. } => "PASTE",
+            FocusState::ReminderFocus { .. } => "REMINDER",
+            FocusState::HelpFocus { .. } => "HELP",
+            FocusState::Invalid => "",
+        }
+    }
+
+    /// Whether the state edits a text input, which reminders shouldn't interrupt
+    pub fn is_input(&self) -> bool {
+        matches!(
+            self,
+            FocusState::FilterFocus { .. }
+                | FocusState::SearchFocus { .. }
+                | FocusState::SortFocus { .. }
+                | FocusState::BatchFocus { .. }
+                | FocusState::RescheduleFocus { .. }
+        )
+    }
+}
+
 impl App {
     /// Constructs a new instance of [`App`].
-    pub fn new(todo_list: TodoList, archive_path: Option<PathBuf>, config: Config) -> Self {
+    pub fn new(
+        todo_list: TodoList,
+        todo_path: PathBuf,
+        archive_path: Option<PathBuf>,
+        config: Config,
+    ) -> Self {
+        let filter = TodoListFilter {
+            word_logic: config.filter.word_logic,
+            word_match: config.filter.word_match,
+            ..Default::default()
+        };
+        let todo_list = SortedFilteredTodoList::new(
+            todo_list,
+            filter,
+            config.sort.order.clone(),
+            config.sort.group_by,
+            config.urgency.clone(),
+        );
+
         Self {
             key_combiner: Combiner::default(),
             config,
+            todo_path,
             archive_path,
             running: true,
-            todo_list: SortedFilteredTodoList::new(todo_list),
+            quit_pending: false,
+            todo_list,
             state: FocusState::default(),
+            sidebar: Sidebar::default(),
+            agenda: AgendaView::default(),
+            board: BoardView::default(),
+            calendar: Calendar {
+                visible: false,
+                day: Local::now().date_naive(),
+            },
+            details_visible: false,
+            click_areas: ClickAreas::default(),
+            last_click: None,
+            pending_reminders: vec![],
+            count_prefix: None,
+            top_pending: false,
+            compact: false,
+            message: None,
         }
     }
 
@@ -96,13 +433,192 @@ impl App {
         std::mem::replace(&mut self.state, FocusState::Invalid)
     }
 
-    /// Handles the tick event of the terminal.
-    pub fn tick(&self) {}
+    /// Writes the todo list to the todo file in its current order,
+    /// appending archived items to the archive file once that succeeded.
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        let archived = self.todo_list.archived();
+        let archive_path = match &self.archive_path {
+            _ if archived.is_empty() => None,
+            Some(path) => Some(path),
+            None => anyhow::bail!("No archive file given"),
+        };
+
+        let mut temp_path = self.todo_path.clone().into_os_string();
+        temp_path.push(".tmp");
+        std::fs::write(&temp_path, self.todo_list.list().to_string())?;
+        std::fs::rename(&temp_path, &self.todo_path)?;
+
+        if let Some(archive_path) = archive_path {
+            let mut archive = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(archive_path)?;
+            for item in archived {
+                writeln!(archive, "{item}")?;
+            }
+        }
+        self.todo_list.clear_archived();
+        self.todo_list.mark_saved();
+        Ok(())
+    }
+
+    /// Reads the items of the archive file, skipping lines that fail to parse.
+    pub fn read_archive(&self) -> anyhow::Result<Vec<TodoItem>> {
+        match &self.archive_path {
+            Some(path) if path.try_exists()? => Ok(std::fs::read_to_string(path)?
+                .lines()
+                .filter_map(|line| line.parse().ok())
+                .collect()),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Returns the amount of rows fitting into the last rendered list.
+    pub fn list_page_size(&self) -> usize {
+        let borders = if self.compact { 1 } else { 2 };
+        usize::from(self.click_areas.list.height.saturating_sub(borders)).max(1)
+    }
+
+    /// Switches between the regular and compact layout for the new terminal size.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.compact = self.config.compact(width, height);
+    }
+
+    /// Shows a message in the status bar.
+    pub fn show_message(&mut self, text: impl Into<String>) {
+        self.message = Some(StatusMessage {
+            text: text.into(),
+            error: false,
+            ticks_left: self.config.status_message_ticks(),
+        });
+    }
+
+    /// Shows an error message in the status bar.
+    pub fn show_error(&mut self, text: impl Into<String>) {
+        self.message = Some(StatusMessage {
+            text: text.into(),
+            error: true,
+            ticks_left: self.config.status_message_ticks(),
+        });
+    }
+
+    /// Handles the tick event of the terminal, returning whether the terminal bell should ring.
+    pub fn tick(&mut self) -> bool {
+        if let Some(message) = &mut self.message {
+            message.ticks_left = message.ticks_left.saturating_sub(1);
+            if message.ticks_left == 0 {
+                self.message = None;
+            }
+        }
+
+        let reminders = self.todo_list.refresh_today();
+        if self.config.reminders.enabled {
+            let list = self.todo_list.list();
+            self.pending_reminders.extend(
+                reminders
+                    .into_iter()
+                    .map(|(index, kind)| (list[index].clone(), kind)),
+            );
+        }
+        if self.pending_reminders.is_empty() || self.state.is_input() {
+            return false;
+        }
+        let reminders = std::mem::take(&mut self.pending_reminders);
+        self.state = match self.take_state() {
+            FocusState::ReminderFocus {
+                reminders: mut previous_reminders,
+                previous_state,
+                scroll,
+            } => {
+                previous_reminders.extend(reminders);
+                FocusState::ReminderFocus {
+                    reminders: previous_reminders,
+                    previous_state,
+                    scroll,
+                }
+            }
+            state => FocusState::ReminderFocus {
+                reminders,
+                previous_state: Box::new(state),
+                scroll: 0,
+            },
+        };
+        self.config.reminders.bell
+    }
 
     /// Set running to false to quit the application.
     pub fn quit(&mut self) {
         self.running = false;
     }
+
+    /// Quits, unless there are unsaved changes and quitting wasn't requested right before.
+    pub fn request_quit(&mut self) {
+        if self.quit_pending || !self.todo_list.is_modified() {
+            self.quit();
+        } else {
+            self.quit_pending = true;
+            let key = self.config.keys.quit;
+            self.show_error(format!("Unsaved changes, press {key} again to quit"));
+        }
+    }
+}
+
+/// A pasted line together with the item parsed from it
+#[derive(Debug)]
+pub struct PastedLine {
+    pub text: String,
+    pub item: Result<TodoItem, String>,
+}
+
+impl PastedLine {
+    /// Parses every non-empty line of the pasted text, using today as creation date
+    /// for lines starting with neither a date nor a completion mark
+    pub fn parse_all(text: &str, today: NaiveDate) -> Vec<PastedLine> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| PastedLine {
+                text: line.to_owned(),
+                item: line.parse().or_else(|error: ItemParseError| {
+                    let (priority, rest) = match line.as_bytes() {
+                        [b'(', b'A'..=b'Z', b')', b' ', ..] => line.split_at(4),
+                        _ => ("", line),
+                    };
+                    if rest.starts_with(|c: char| c.is_ascii_digit()) || rest.starts_with("x ") {
+                        return Err(error.to_string());
+                    }
+                    format!("{priority}{} {rest}", today.format("%Y-%m-%d"))
+                        .parse()
+                        .map_err(|_| error.to_string())
+                }),
+            })
+            .collect()
+    }
+}
+
+/// Parses a date entered by the user, either as `YYYY-MM-DD` or relative to today
+/// as amount with an optional unit like `3`, `+3d`, `2w` or `1m`.
+pub fn parse_date_input(input: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    let relative = input.strip_prefix('+').unwrap_or(input);
+    let (amount, unit) = relative.split_at(
+        relative
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(relative.len()),
+    );
+    let amount: u32 = amount
+        .parse()
+        .map_err(|_| format!("Invalid date '{input}'"))?;
+    let unit = match unit {
+        "" => RecurringUnit::Days,
+        unit => unit.parse()?,
+    };
+    unit.add_to(today, amount)
+        .ok_or_else(|| format!("Date '{input}' is out of range"))
 }
 
 impl Default for TodoListFilter {
@@ -112,12 +628,185 @@ impl Default for TodoListFilter {
             completion: None,
             priority: None,
             t: true,
+            due: DueFilter::Any,
+            hidden: false,
+            day: None,
+            categories: BTreeSet::new(),
+            word_logic: WordLogic::default(),
+            word_match: WordMatch::default(),
+        }
+    }
+}
+
+impl Sidebar {
+    pub fn table_state_mut(&self) -> RefMut<'_, TableState> {
+        self.table_state.borrow_mut()
+    }
+}
+
+impl BoardView {
+    /// Returns the table state of the column, adding missing states
+    pub fn column_state_mut(&self, column: usize) -> RefMut<'_, TableState> {
+        let mut states = self.column_states.borrow_mut();
+        if states.len() <= column {
+            states.resize_with(column + 1, TableState::default);
+        }
+        RefMut::map(states, |states| &mut states[column])
+    }
+
+    /// Returns the list index of the selected card in the focused column
+    pub fn selected(&self, cards: &[Vec<usize>]) -> Option<usize> {
+        let selected = self.column_state_mut(self.column).selected()?;
+        cards.get(self.column)?.get(selected).copied()
+    }
+
+    /// Selects the card with the given list index in the column
+    pub fn select(&self, cards: &[Vec<usize>], column: usize, index: usize) {
+        let position = cards[column].iter().position(|i| *i == index);
+        self.column_state_mut(column).select(position);
+    }
+
+    /// Clamps the selections of all columns to their amount of cards
+    pub fn clamp_selections(&self, cards: &[Vec<usize>]) {
+        for (column, cards) in cards.iter().enumerate() {
+            let mut state = self.column_state_mut(column);
+            let selected = match cards.len() {
+                0 => None,
+                len => Some(state.selected().unwrap_or_default().min(len - 1)),
+            };
+            state.select(selected);
+        }
+    }
+}
+
+impl AgendaView {
+    pub fn table_state_mut(&self) -> RefMut<'_, TableState> {
+        self.table_state.borrow_mut()
+    }
+
+    /// Returns the list index and listed date of the selected item
+    pub fn selected(&self, rows: &[AgendaRow]) -> Option<(usize, DateKind)> {
+        match rows.get(self.table_state.borrow().selected()?)? {
+            AgendaRow::Item { index, kind } => Some((*index, *kind)),
+            AgendaRow::Header(_) => None,
+        }
+    }
+
+    /// Moves the selection by the given amount of items, skipping headers.
+    ///
+    /// An offset of zero moves the selection onto the next item row, if it is on a header.
+    pub fn move_selection(&self, rows: &[AgendaRow], offset: isize) {
+        let item_rows: Vec<_> = rows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, row)| matches!(row, AgendaRow::Item { .. }).then_some(i))
+            .collect();
+        let mut table_state = self.table_state.borrow_mut();
+        if item_rows.is_empty() {
+            table_state.select(None);
+            return;
+        }
+
+        let selected = table_state.selected().unwrap_or_default();
+        let position = item_rows.partition_point(|row| *row < selected) as isize + offset;
+        let position = position.clamp(0, item_rows.len() as isize - 1) as usize;
+        table_state.select(Some(item_rows[position]));
+    }
+}
+
+impl Category {
+    /// Adds the category to the set if absent, removes it otherwise
+    pub fn toggle_in(self, set: &mut BTreeSet<Category>) {
+        if !set.remove(&self) {
+            set.insert(self);
+        }
+    }
+
+    fn matches(&self, item: &TodoItem) -> bool {
+        match self {
+            Category::Project(project) => item.projects().any(|p| p == project),
+            Category::Context(context) => item.contexts().any(|c| c == context),
         }
     }
 }
 
 impl TodoListFilter {
-    pub fn applies(&self, item: &TodoItem) -> bool {
+    /// Cycles between ignoring completion, completed and incomplete items
+    pub fn cycle_completion(&mut self) {
+        self.completion = match self.completion {
+            None => Some(true),
+            Some(true) => Some(false),
+            Some(false) => None,
+        };
+    }
+
+    /// Cycles between ignoring priority, each of the given priorities and no priority
+    pub fn cycle_priority(&mut self, priorities: &BTreeSet<char>) {
+        self.priority = match self.priority {
+            None => Some(priorities.first().copied()),
+            Some(Some(current)) => Some(
+                priorities
+                    .range((Bound::Excluded(current), Bound::Unbounded))
+                    .next()
+                    .copied(),
+            ),
+            Some(None) => None,
+        };
+    }
+}
+
+impl DueFilter {
+    /// Returns the next state when cycling through the filter states
+    pub fn next(self, within_days: u32) -> Self {
+        match self {
+            DueFilter::Any => DueFilter::Overdue,
+            DueFilter::Overdue => DueFilter::Today,
+            DueFilter::Today => DueFilter::Within(within_days),
+            DueFilter::Within(_) => DueFilter::NoDue,
+            DueFilter::NoDue => DueFilter::Any,
+        }
+    }
+
+    /// Checks whether an item with the given due date passes the filter
+    pub fn applies(&self, due: Option<NaiveDate>, today: NaiveDate) -> bool {
+        match (self, due) {
+            (DueFilter::Any, _) => true,
+            (DueFilter::NoDue, due) => due.is_none(),
+            (_, None) => false,
+            (DueFilter::Overdue, Some(due)) => due < today,
+            (DueFilter::Today, Some(due)) => due == today,
+            (DueFilter::Within(days), Some(due)) => {
+                today <= due && (due - today).num_days() <= *days as i64
+            }
+        }
+    }
+}
+
+impl TodoListFilter {
+    pub fn applies(&self, item: &TodoItem, today: NaiveDate) -> bool {
+        if let Some(day) = self.day {
+            if item.due != Some(day) && item.t != Some(day) {
+                return false;
+            }
+        } else {
+            if self.t {
+                if let Some(t_date) = item.t {
+                    if today < t_date {
+                        return false;
+                    }
+                }
+            }
+
+            if !self.due.applies(item.due, today) {
+                return false;
+            }
+        }
+
+        self.applies_to_attributes(item)
+    }
+
+    /// Checks the filters unrelated to dates
+    pub fn applies_to_attributes(&self, item: &TodoItem) -> bool {
         if self
             .completion
             .is_some_and(|c| c != item.completion_date.is_some())
@@ -129,31 +818,34 @@ impl TodoListFilter {
             return false;
         }
 
-        if self.t {
-            if let Some(t_date) = item.t {
-                if Local::now().date_naive() < t_date {
-                    return false;
-                }
-            }
+        if item.hidden && !self.hidden {
+            return false;
+        }
+
+        if !self.categories.is_empty() && !self.categories.iter().any(|c| c.matches(item)) {
+            return false;
         }
 
         if !self.input_field.value().is_empty() {
             let lower = self.input_field.value().to_lowercase();
-            let words: Vec<_> = lower.split_whitespace().collect();
-            let mut matched = false;
-
-            for part in item.content_parts() {
-                match &part.content {
+            let tokens: Vec<_> = item
+                .content_parts()
+                .map(|part| match &part.content {
                     Content::Word(text) | Content::Context(text) | Content::Project(text) => {
-                        for word in &words {
-                            if text.to_lowercase().contains(word) {
-                                matched = true;
-                                break;
-                            }
-                        }
+                        text.to_lowercase()
                     }
-                }
-            }
+                })
+                .collect();
+            let word_matches = |word: &str| {
+                tokens
+                    .iter()
+                    .any(|token| self.word_match.matches(token, word))
+            };
+
+            let matched = match self.word_logic {
+                WordLogic::All => lower.split_whitespace().all(word_matches),
+                WordLogic::Any => lower.split_whitespace().any(word_matches),
+            };
 
             if !matched {
                 return false;
@@ -165,29 +857,697 @@ impl TodoListFilter {
 }
 
 impl SortedFilteredTodoList {
-    pub fn new(list: TodoList) -> Self {
-        let filter = TodoListFilter::default();
+    pub fn new(
+        list: TodoList,
+        filter: TodoListFilter,
+        sort: Vec<SortKey>,
+        grouping: Grouping,
+        urgency: UrgencyCoefficients,
+    ) -> Self {
         let view_indices = Vec::with_capacity(list.len());
 
         let mut this = Self {
             list,
             filter,
+            sort,
             list_table_state: RefCell::new(TableState::new().with_selected(0)),
             view_indices,
+            grouping,
+            collapsed: BTreeSet::new(),
+            rows: vec![],
+            search: Input::new("".to_owned()),
+            modified: false,
+            today: Local::now().date_naive(),
+            last_reminded: Local::now().date_naive(),
+            urgency,
+            marks: BTreeSet::new(),
+            archived: vec![],
+            history: vec![],
+            revision: 0,
         };
         this.update_view_indices();
         this
     }
 
+    /// Recomputes the view if the date changed since the last update, returning the open and
+    /// not hidden items whose due or threshold date was reached since the last reminders
+    pub fn refresh_today(&mut self) -> Vec<(usize, DateKind)> {
+        let today = Local::now().date_naive();
+        if today != self.today {
+            self.update_view_indices();
+        }
+        self.reminders_until(today)
+    }
+
+    /// Returns the open and not hidden items whose due or threshold date lies after the last
+    /// reminded date up to the given one, remembering it as reminded
+    fn reminders_until(&mut self, day: NaiveDate) -> Vec<(usize, DateKind)> {
+        let previous = self.last_reminded;
+        if day <= previous {
+            return vec![];
+        }
+        self.last_reminded = day;
+
+        let reached =
+            |date: Option<NaiveDate>| date.is_some_and(|date| previous < date && date <= day);
+        self.list
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.completion_date.is_none() && !item.hidden)
+            .flat_map(|(index, item)| {
+                [(item.due, DateKind::Due), (item.t, DateKind::Threshold)]
+                    .into_iter()
+                    .filter(|(date, _)| reached(*date))
+                    .map(move |(_, kind)| (index, kind))
+            })
+            .collect()
+    }
+
+    /// Recomputes the view, keeping the selected item selected if it is still visible
     fn update_view_indices(&mut self) {
+        let selected = self.selected_index();
+        self.today = Local::now().date_naive();
+        let today = self.today;
         self.view_indices.clear();
         self.view_indices.extend(
             self.list
                 .iter()
                 .enumerate()
-                .filter_map(|(i, item)| self.filter.applies(item).then_some(i)),
+                .filter_map(|(i, item)| self.filter.applies(item, today).then_some(i)),
         );
-        self.view_indices.sort_by_key(|i| &self.list[*i]);
+        // Urgency is only needed for sorting here, the ui computes it for the shown items
+        let mut urgencies = vec![0.0; self.list.len()];
+        if self.sort.iter().any(|key| key.field == SortField::Urgency) {
+            for index in self.view_indices.iter().copied() {
+                urgencies[index] = Urgency::of(&self.list[index], today, &self.urgency).value();
+            }
+        }
+        let sort_item = |index: usize| SortItem {
+            index,
+            item: &self.list[index],
+            urgency: urgencies[index],
+        };
+        self.view_indices.sort_by(|a, b| {
+            let (a, b) = (sort_item(*a), sort_item(*b));
+            self.sort.iter().fold(Ordering::Equal, |ordering, key| {
+                ordering.then_with(|| key.compare(a, b))
+            })
+        });
+
+        self.update_rows(today);
+
+        let position = selected.and_then(|s| self.item_row(s));
+        let fallback = self
+            .list_table_state
+            .borrow()
+            .selected()
+            .map_or_else(|| self.item_rows().next(), |row| self.nearest_item_row(row));
+        self.table_state_mut().select(position.or(fallback));
+    }
+
+    fn update_rows(&mut self, today: NaiveDate) {
+        self.rows.clear();
+        if self.grouping == Grouping::None {
+            self.rows
+                .extend(self.view_indices.iter().copied().map(ViewRow::Item));
+            return;
+        }
+
+        let mut groups: BTreeMap<Group, Vec<usize>> = BTreeMap::new();
+        for index in self.view_indices.iter().copied() {
+            for group in self.grouping.groups_of(&self.list[index], today) {
+                groups.entry(group).or_default().push(index);
+            }
+        }
+
+        for (group, indices) in groups {
+            let collapsed = self.collapsed.contains(&group);
+            self.rows.push(ViewRow::Header {
+                group,
+                count: indices.len(),
+                collapsed,
+            });
+            if !collapsed {
+                self.rows.extend(indices.into_iter().map(ViewRow::Item));
+            }
+        }
+    }
+
+    /// Returns the first row showing the item with the given index into the underlying list
+    pub fn item_row(&self, index: usize) -> Option<usize> {
+        self.rows
+            .iter()
+            .position(|row| *row == ViewRow::Item(index))
+    }
+
+    /// Returns the item row closest to the given row, preferring following rows
+    fn nearest_item_row(&self, row: usize) -> Option<usize> {
+        let is_item = |row: &usize| matches!(self.rows[*row], ViewRow::Item(_));
+        let row = row.min(self.rows.len().saturating_sub(1));
+        (row..self.rows.len())
+            .find(is_item)
+            .or_else(|| (0..row).rev().find(is_item))
+    }
+
+    /// Returns the indices of all rows showing items
+    fn item_rows(&self) -> impl Iterator<Item = usize> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, row)| matches!(row, ViewRow::Item(_)).then_some(i))
+    }
+
+    /// Returns the index into the underlying list of the selected item
+    pub fn selected_index(&self) -> Option<usize> {
+        let selected = self.list_table_state.borrow().selected()?;
+        match self.rows.get(selected)? {
+            ViewRow::Item(index) => Some(*index),
+            ViewRow::Header { .. } => None,
+        }
+    }
+
+    /// Selects the item with the given index into the underlying list, if it is visible
+    pub fn select_index(&self, index: usize) -> bool {
+        let row = self.item_row(index);
+        if row.is_some() {
+            self.table_state_mut().select(row);
+        }
+        row.is_some()
+    }
+
+    /// Selects the item at the given position of the view, clamped to the last item
+    pub fn select_nth_item(&self, n: usize) {
+        let Some(last) = self.item_rows().count().checked_sub(1) else {
+            return;
+        };
+        let row = self.item_rows().nth(n.min(last));
+        self.table_state_mut().select(row);
+    }
+
+    /// Moves the selection by the given amount of items, optionally wrapping around
+    pub fn move_selection(&self, offset: isize, wrap: bool) {
+        let item_rows: Vec<_> = self.item_rows().collect();
+        if item_rows.is_empty() {
+            return;
+        }
+
+        let mut table_state = self.table_state_mut();
+        let selected = table_state.selected().unwrap_or_default();
+        // A selected header lies between the item rows before and after it
+        let current = match item_rows.binary_search(&selected) {
+            Ok(position) => position as isize,
+            Err(following) if offset > 0 => following as isize - 1,
+            Err(following) => following as isize,
+        };
+        let len = item_rows.len() as isize;
+        let next = if wrap {
+            (current + offset).rem_euclid(len)
+        } else {
+            (current + offset).clamp(0, len - 1)
+        };
+        table_state.select(Some(item_rows[next as usize]));
+    }
+
+    /// Selects the given row if it shows an item
+    pub fn select_row(&self, row: usize) -> bool {
+        let is_item = matches!(self.rows.get(row), Some(ViewRow::Item(_)));
+        if is_item {
+            self.table_state_mut().select(Some(row));
+        }
+        is_item
+    }
+
+    /// Marks the item with the given index into the underlying list as completed today,
+    /// or as incomplete if it already is completed
+    pub fn toggle_completion(&mut self, index: usize) {
+        self.checkpoint();
+        let item = &mut self.list[index];
+        item.completion_date = match item.completion_date {
+            Some(_) => None,
+            None => Some(self.today),
+        };
+        self.modified = true;
+        self.update_view_indices();
+    }
+
+    /// Sets the due or threshold date of the item with the given index
+    pub fn set_date(&mut self, index: usize, kind: DateKind, date: NaiveDate) {
+        self.checkpoint();
+        let item = &mut self.list[index];
+        match kind {
+            DateKind::Due => item.due = Some(date),
+            DateKind::Threshold => item.t = Some(date),
+        }
+        self.modified = true;
+        self.update_view_indices();
+    }
+
+    /// Distributes the filtered items in view order onto the board columns
+    pub fn board_cards(&self, by: BoardColumns, columns: &[String]) -> Vec<Vec<usize>> {
+        let mut cards = vec![vec![]; columns.len()];
+        if columns.is_empty() {
+            return cards;
+        }
+        for index in self.view_indices.iter().copied() {
+            cards[by.column_of(&self.list[index], columns)].push(index);
+        }
+
+        cards
+    }
+
+    /// Moves the item with the given index from one board column to another
+    pub fn move_to_column(&mut self, index: usize, by: BoardColumns, from: &str, to: &str) {
+        self.checkpoint();
+        by.move_item(&mut self.list[index], from, to);
+        self.modified = true;
+        self.update_view_indices();
+    }
+
+    /// Lists the open items passing the filters unrelated to dates under day headings,
+    /// from today until the given amount of days ahead
+    pub fn agenda_rows(&self, days: u32, undated: bool) -> Vec<AgendaRow> {
+        let today = self.today;
+        let last = today + TimeDelta::days(days.into());
+        let mut overdue = vec![];
+        let mut dated: BTreeMap<NaiveDate, Vec<_>> = BTreeMap::new();
+        let mut without = vec![];
+        for (index, item) in self.list.iter().enumerate() {
+            if item.completion_date.is_some() || !self.filter.applies_to_attributes(item) {
+                continue;
+            }
+            match item.due {
+                Some(due) if due < today => overdue.push((due, index)),
+                Some(due) if due <= last => {
+                    dated.entry(due).or_default().push((index, DateKind::Due));
+                }
+                _ => {}
+            }
+            if let Some(t) = item.t.filter(|t| (today..=last).contains(t)) {
+                dated
+                    .entry(t)
+                    .or_default()
+                    .push((index, DateKind::Threshold));
+            }
+            if item.due.is_none() && item.t.is_none() {
+                without.push(index);
+            }
+        }
+
+        let mut rows = vec![];
+        if !overdue.is_empty() {
+            overdue.sort();
+            rows.push(AgendaRow::Header(AgendaSection::Overdue));
+            rows.extend(overdue.into_iter().map(|(_, index)| AgendaRow::Item {
+                index,
+                kind: DateKind::Due,
+            }));
+        }
+        for day in today.iter_days().take_while(|day| *day <= last) {
+            rows.push(AgendaRow::Header(AgendaSection::Day(day)));
+            let mut items = dated.remove(&day).unwrap_or_default();
+            items.sort_by_key(|(index, _)| {
+                let priority = self.list[*index].priority;
+                (priority.is_none(), priority, *index)
+            });
+            rows.extend(
+                items
+                    .into_iter()
+                    .map(|(index, kind)| AgendaRow::Item { index, kind }),
+            );
+        }
+        if undated && !without.is_empty() {
+            rows.push(AgendaRow::Header(AgendaSection::Undated));
+            rows.extend(without.into_iter().map(|index| AgendaRow::Item {
+                index,
+                kind: DateKind::Due,
+            }));
+        }
+
+        rows
+    }
+
+    /// Returns all priorities occurring in the underlying list
+    pub fn priorities(&self) -> BTreeSet<char> {
+        self.list.iter().filter_map(|item| item.priority).collect()
+    }
+
+    /// Swaps the selected item with the item in the neighbouring row of the view, reordering
+    /// the underlying list. Items are not moved past group headers.
+    ///
+    /// Returns whether the item was moved.
+    pub fn move_selected(&mut self, offset: isize) -> bool {
+        let Some(selected) = self.selected_index() else {
+            return false;
+        };
+        let Some((row, neighbour)) = self
+            .list_table_state
+            .borrow()
+            .selected()
+            .and_then(|row| row.checked_add_signed(offset))
+            .and_then(|row| match self.rows.get(row)? {
+                ViewRow::Item(index) => Some((row, *index)),
+                ViewRow::Header { .. } => None,
+            })
+        else {
+            return false;
+        };
+
+        self.checkpoint();
+        self.list.swap(selected, neighbour);
+        if self.marks.contains(&selected) != self.marks.contains(&neighbour) {
+            for index in [selected, neighbour] {
+                if !self.marks.remove(&index) {
+                    self.marks.insert(index);
+                }
+            }
+        }
+        self.modified = true;
+        self.update_view_indices();
+        self.table_state_mut().select(Some(row));
+        true
+    }
+
+    /// Has the underlying list changed since it was last saved?
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.modified = false;
+    }
+
+    /// Remembers the current state of the list as undo step
+    fn checkpoint(&mut self) {
+        if self.history.len() == UNDO_LIMIT {
+            self.history.remove(0);
+        }
+        self.history
+            .push((self.list.clone(), self.archived.clone()));
+        self.revision += 1;
+    }
+
+    /// Restores the state before the last change, returning false if there is none
+    pub fn undo(&mut self) -> bool {
+        let Some((list, archived)) = self.history.pop() else {
+            return false;
+        };
+        self.list = list;
+        self.archived = archived;
+        self.marks.clear();
+        self.revision += 1;
+        self.modified = true;
+        self.update_view_indices();
+        true
+    }
+
+    /// Returns a number changing with every change of the underlying list
+    pub fn revision(&self) -> usize {
+        self.revision
+    }
+
+    /// Applies the function to all items with the given indices as a single undo step
+    pub fn edit_items(&mut self, indices: &[usize], mut edit: impl FnMut(&mut TodoItem)) {
+        if indices.is_empty() {
+            return;
+        }
+        self.checkpoint();
+        for index in indices {
+            edit(&mut self.list[*index]);
+        }
+        self.modified = true;
+        self.update_view_indices();
+    }
+
+    /// Appends the items to the list as a single undo step
+    pub fn add_items(&mut self, items: Vec<TodoItem>) {
+        if items.is_empty() {
+            return;
+        }
+        self.checkpoint();
+        self.list.extend(items);
+        self.modified = true;
+        self.update_view_indices();
+    }
+
+    /// Removes the items with the given indices as a single undo step,
+    /// keeping them to be appended to the archive on saving if `archive` is set
+    pub fn remove_items(&mut self, indices: &[usize], archive: bool) {
+        if indices.is_empty() {
+            return;
+        }
+        self.checkpoint();
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        let removed: Vec<_> = indices
+            .iter()
+            .rev()
+            .map(|index| self.list.remove(*index))
+            .collect();
+        if archive {
+            self.archived.extend(removed.into_iter().rev());
+        }
+        self.marks.clear();
+        self.modified = true;
+        self.update_view_indices();
+    }
+
+    /// Returns the items archived since the last save
+    pub fn archived(&self) -> &[TodoItem] {
+        &self.archived
+    }
+
+    /// Forgets the archived items once written, dropping the undo steps that would restore them
+    pub fn clear_archived(&mut self) {
+        let written = self.archived.len();
+        let restoring = self
+            .history
+            .iter()
+            .take_while(|(_, archived)| archived.len() < written)
+            .count();
+        self.history.drain(..restoring);
+        for (_, archived) in &mut self.history {
+            archived.clear();
+        }
+        self.archived.clear();
+    }
+
+    pub fn marks(&self) -> &BTreeSet<usize> {
+        &self.marks
+    }
+
+    pub fn set_marks(&mut self, marks: BTreeSet<usize>) {
+        self.marks = marks;
+    }
+
+    pub fn is_marked(&self, index: usize) -> bool {
+        self.marks.contains(&index)
+    }
+
+    pub fn toggle_mark(&mut self, index: usize) {
+        if !self.marks.remove(&index) {
+            self.marks.insert(index);
+        }
+    }
+
+    /// Returns the marked items, or the selected item if none is marked
+    pub fn targets(&self) -> Vec<usize> {
+        match self.marks.is_empty() {
+            true => self.selected_index().into_iter().collect(),
+            false => self.marks.iter().copied().collect(),
+        }
+    }
+
+    /// Returns the indices of the items shown between the given rows, both included
+    pub fn items_between_rows(&self, a: usize, b: usize) -> impl Iterator<Item = usize> + '_ {
+        self.rows[a.min(b)..=a.max(b).min(self.rows.len().saturating_sub(1))]
+            .iter()
+            .filter_map(|row| match row {
+                ViewRow::Item(index) => Some(*index),
+                ViewRow::Header { .. } => None,
+            })
+    }
+
+    pub fn rows(&self) -> &[ViewRow] {
+        &self.rows
+    }
+
+    pub fn list(&self) -> &TodoList {
+        &self.list
+    }
+
+    pub fn selected_item(&self) -> Option<&TodoItem> {
+        self.selected_index().map(|i| &self.list[i])
+    }
+
+    /// Returns the date the view was last computed for
+    pub fn today(&self) -> NaiveDate {
+        self.today
+    }
+
+    pub fn urgency_of(&self, item: &TodoItem) -> Urgency {
+        Urgency::of(item, self.today, &self.urgency)
+    }
+
+    pub fn grouping(&self) -> Grouping {
+        self.grouping
+    }
+
+    pub fn set_grouping(&mut self, grouping: Grouping) {
+        self.grouping = grouping;
+        self.collapsed.clear();
+        self.update_view_indices();
+    }
+
+    /// Collapses or expands the group containing the selected row
+    pub fn toggle_selected_group(&mut self) {
+        let selected = self.list_table_state.borrow().selected();
+        if let Some(selected) = selected {
+            self.toggle_group_at(selected);
+        }
+    }
+
+    /// Collapses or expands the group containing the given row, selecting its header
+    pub fn toggle_group_at(&mut self, row: usize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let group = self.rows[..=row.min(self.rows.len() - 1)]
+            .iter()
+            .rev()
+            .find_map(|row| match row {
+                ViewRow::Header { group, .. } => Some(group.clone()),
+                ViewRow::Item(_) => None,
+            });
+
+        if let Some(group) = group {
+            if !self.collapsed.remove(&group) {
+                self.collapsed.insert(group.clone());
+            }
+            self.update_view_indices();
+            let header = self.rows.iter().position(
+                |row| matches!(row, ViewRow::Header { group: header, .. } if *header == group),
+            );
+            self.table_state_mut().select(header);
+        }
+    }
+
+    pub fn expand_all_groups(&mut self) {
+        self.collapsed.clear();
+        self.update_view_indices();
+    }
+
+    pub fn search(&self) -> &Input {
+        &self.search
+    }
+
+    pub fn mutate_search(&mut self, f: impl FnOnce(&mut Input)) {
+        f(&mut self.search);
+    }
+
+    /// Returns the lowercase words of the current search
+    pub fn search_words(&self) -> Vec<String> {
+        self.search
+            .value()
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    fn matches_search(item: &TodoItem, words: &[String]) -> bool {
+        item.content_parts().any(|part| match &part.content {
+            Content::Word(text) | Content::Context(text) | Content::Project(text) => {
+                let text = text.to_lowercase();
+                words.iter().any(|word| text.contains(word))
+            }
+        })
+    }
+
+    /// Selects the next item matching the search, starting at the given row
+    ///
+    /// The search wraps around and returns whether a match was found.
+    pub fn select_search_match(&self, start: usize, forward: bool) -> bool {
+        let words = self.search_words();
+        let len = self.rows.len();
+        if words.is_empty() || len == 0 {
+            return false;
+        }
+
+        let start = start % len;
+        let found = (0..len)
+            .map(|offset| {
+                if forward {
+                    (start + offset) % len
+                } else {
+                    (start + len - offset) % len
+                }
+            })
+            .find(|row| match self.rows[*row] {
+                ViewRow::Item(index) => Self::matches_search(&self.list[index], &words),
+                ViewRow::Header { .. } => false,
+            });
+
+        if found.is_some() {
+            self.table_state_mut().select(found);
+        }
+        found.is_some()
+    }
+
+    /// Counts the items of every project and context in the underlying list
+    ///
+    /// Hidden items declare their categories without being counted.
+    /// Categories present in the filter are always contained.
+    pub fn category_counts(&self) -> BTreeMap<Category, CategoryCount> {
+        let mut counts: BTreeMap<_, CategoryCount> = self
+            .filter
+            .categories
+            .iter()
+            .map(|c| (c.clone(), CategoryCount::default()))
+            .collect();
+
+        for item in self.list.iter() {
+            let categories = item
+                .projects()
+                .map(|p| Category::Project(p.to_owned()))
+                .chain(item.contexts().map(|c| Category::Context(c.to_owned())))
+                .collect::<BTreeSet<_>>();
+            for category in categories {
+                let count = counts.entry(category).or_default();
+                if item.hidden {
+                    continue;
+                }
+                count.total += 1;
+                if item.completion_date.is_none() {
+                    count.open += 1;
+                }
+            }
+        }
+
+        counts
+    }
+
+    /// Counts the items due or reaching their threshold per day within the given range,
+    /// considering only the filters unrelated to dates
+    pub fn day_counts(&self, days: RangeInclusive<NaiveDate>) -> BTreeMap<NaiveDate, DayCount> {
+        let mut counts: BTreeMap<_, DayCount> = BTreeMap::new();
+        for item in self.list.iter() {
+            if !self.filter.applies_to_attributes(item) {
+                continue;
+            }
+            if let Some(due) = item.due.filter(|due| days.contains(due)) {
+                counts.entry(due).or_default().due += 1;
+            }
+            if let Some(t) = item.t.filter(|t| days.contains(t)) {
+                counts.entry(t).or_default().t += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Returns the amount of items passing the filter, including those in collapsed groups
+    pub fn visible_count(&self) -> usize {
+        self.view_indices.len()
     }
 
     pub fn items(&self) -> impl ExactSizeIterator<Item = &TodoItem> {
@@ -203,7 +1563,278 @@ impl SortedFilteredTodoList {
         self.update_view_indices();
     }
 
-    pub fn table_state_mut(&self) -> RefMut<TableState> {
+    pub fn sort(&self) -> &[SortKey] {
+        &self.sort
+    }
+
+    /// Whether the view is primarily sorted by file order, so moving items reorders the file
+    pub fn is_file_sorted(&self) -> bool {
+        self.sort
+            .first()
+            .is_some_and(|key| key.field == SortField::File)
+    }
+
+    pub fn set_sort(&mut self, sort: Vec<SortKey>) {
+        self.sort = sort;
+        self.update_view_indices();
+    }
+
+    pub fn table_state_mut(&self) -> RefMut<'_, TableState> {
         self.list_table_state.borrow_mut()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Days, Local, NaiveDate};
+    use tui_input::Input;
+
+    use super::{
+        parse_date_input, DueFilter, PastedLine, SortedFilteredTodoList, TodoListFilter, ViewRow,
+    };
+    use crate::{
+        config::{UrgencyCoefficients, WordLogic, WordMatch},
+        view::{DateKind, Group, Grouping, SortField},
+    };
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parse_date_input_absolute_and_relative() {
+        let today = date(2024, 1, 31);
+        assert_eq!(parse_date_input("2024-03-05", today), Ok(date(2024, 3, 5)));
+        assert_eq!(parse_date_input(" 3 ", today), Ok(date(2024, 2, 3)));
+        assert_eq!(parse_date_input("+3d", today), Ok(date(2024, 2, 3)));
+        assert_eq!(parse_date_input("2w", today), Ok(date(2024, 2, 14)));
+        assert_eq!(parse_date_input("1m", today), Ok(date(2024, 2, 29)));
+        assert_eq!(parse_date_input("1y", today), Ok(date(2025, 1, 31)));
+        assert_eq!(parse_date_input("0", today), Ok(today));
+    }
+
+    #[test]
+    fn parse_date_input_rejects_invalid() {
+        let today = date(2024, 1, 31);
+        for input in ["", "d", "-1", "3x", "2024-13-01", "tomorrow"] {
+            assert!(parse_date_input(input, today).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn pasted_lines_default_to_today_as_creation_date() {
+        let text = "  Buy milk +home \n\n(B) Call bob\n2024-01-02 Existing\n2024-13-01 Bad\nx done";
+        let lines = PastedLine::parse_all(text, date(2024, 1, 10));
+
+        let texts: Vec<_> = lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "Buy milk +home",
+                "(B) Call bob",
+                "2024-01-02 Existing",
+                "2024-13-01 Bad",
+                "x done"
+            ]
+        );
+        let items: Vec<_> = lines
+            .iter()
+            .map(|line| line.item.as_ref().ok().map(ToString::to_string))
+            .collect();
+        assert_eq!(
+            items,
+            [
+                Some("2024-01-10 Buy milk +home".to_owned()),
+                Some("(B) 2024-01-10 Call bob".to_owned()),
+                Some("2024-01-02 Existing".to_owned()),
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn due_filter_applies_to_due_dates() {
+        let today = date(2024, 1, 10);
+        let dates = [
+            None,
+            Some(date(2024, 1, 9)),
+            Some(today),
+            Some(date(2024, 1, 13)),
+            Some(date(2024, 1, 14)),
+        ];
+        let passing = |filter: DueFilter| {
+            dates
+                .iter()
+                .map(|due| filter.applies(*due, today))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(passing(DueFilter::Any), [true, true, true, true, true]);
+        assert_eq!(
+            passing(DueFilter::Overdue),
+            [false, true, false, false, false]
+        );
+        assert_eq!(
+            passing(DueFilter::Today),
+            [false, false, true, false, false]
+        );
+        assert_eq!(
+            passing(DueFilter::Within(3)),
+            [false, false, true, true, false]
+        );
+        assert_eq!(
+            passing(DueFilter::NoDue),
+            [true, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn filter_words_combine_by_logic_and_match() {
+        let item = "2024-01-01 Buy oat milk +shopping @store".parse().unwrap();
+        let passes = |input: &str, word_logic, word_match| {
+            let filter = TodoListFilter {
+                input_field: Input::new(input.to_owned()),
+                word_logic,
+                word_match,
+                ..TodoListFilter::default()
+            };
+            filter.applies_to_attributes(&item)
+        };
+
+        assert!(passes("MILK shop", WordLogic::All, WordMatch::Substring));
+        assert!(!passes("milk bread", WordLogic::All, WordMatch::Substring));
+        assert!(passes("milk bread", WordLogic::Any, WordMatch::Substring));
+        assert!(!passes(
+            "bread butter",
+            WordLogic::Any,
+            WordMatch::Substring
+        ));
+        assert!(passes("mil sto", WordLogic::All, WordMatch::Prefix));
+        assert!(!passes("ilk", WordLogic::All, WordMatch::Prefix));
+        assert!(passes("milk store", WordLogic::All, WordMatch::Exact));
+        assert!(!passes("mil", WordLogic::Any, WordMatch::Exact));
+    }
+
+    #[test]
+    fn reminders_survive_a_rebuild_after_midnight() {
+        let today = Local::now().date_naive();
+        let tomorrow = today + Days::new(1);
+        let list = format!("{today} Water plants due:{tomorrow}\n{today} Air out t:{today}")
+            .parse()
+            .unwrap();
+        let mut todo_list = SortedFilteredTodoList::new(
+            list,
+            TodoListFilter::default(),
+            vec![],
+            Grouping::None,
+            UrgencyCoefficients::default(),
+        );
+
+        // a rebuild after midnight advances the view date before the next tick
+        todo_list.today = tomorrow;
+        todo_list.mutate_filter(|_| {});
+
+        assert_eq!(todo_list.reminders_until(today), vec![]);
+        assert_eq!(
+            todo_list.reminders_until(tomorrow),
+            vec![(0, DateKind::Due)]
+        );
+        assert_eq!(todo_list.reminders_until(tomorrow), vec![]);
+    }
+
+    #[test]
+    fn grouped_rows_and_selection_across_headers() {
+        let list = "2024-01-01 A +x\n2024-01-01 B +y\n2024-01-01 C +x +y\n2024-01-01 D"
+            .parse()
+            .unwrap();
+        let mut todo_list = SortedFilteredTodoList::new(
+            list,
+            TodoListFilter::default(),
+            vec![SortField::File.ascending()],
+            Grouping::Project,
+            UrgencyCoefficients::default(),
+        );
+        let header = |group, count, collapsed| ViewRow::Header {
+            group,
+            count,
+            collapsed,
+        };
+        let x = || Group::Project("x".to_owned());
+        let y = || Group::Project("y".to_owned());
+
+        assert_eq!(
+            todo_list.rows(),
+            [
+                header(x(), 2, false),
+                ViewRow::Item(0),
+                ViewRow::Item(2),
+                header(y(), 2, false),
+                ViewRow::Item(1),
+                ViewRow::Item(2),
+                header(Group::Without, 1, false),
+                ViewRow::Item(3),
+            ]
+        );
+        assert_eq!(todo_list.selected_index(), Some(0));
+        todo_list.move_selection(2, false);
+        assert_eq!(todo_list.selected_index(), Some(1));
+        todo_list.move_selection(-3, true);
+        assert_eq!(todo_list.selected_index(), Some(3));
+
+        todo_list.toggle_group_at(1);
+        assert_eq!(
+            todo_list.rows(),
+            [
+                header(x(), 2, true),
+                header(y(), 2, false),
+                ViewRow::Item(1),
+                ViewRow::Item(2),
+                header(Group::Without, 1, false),
+                ViewRow::Item(3),
+            ]
+        );
+        assert_eq!(todo_list.selected_index(), None);
+        todo_list.move_selection(1, false);
+        assert_eq!(todo_list.selected_index(), Some(1));
+        todo_list.move_selection(-1, false);
+        assert_eq!(todo_list.selected_index(), Some(1));
+    }
+
+    #[test]
+    fn move_selected_stays_within_its_group() {
+        let list = "2024-01-01 First +x\n2024-01-01 Second +y\n2024-01-01 Third +x"
+            .parse()
+            .unwrap();
+        let mut todo_list = SortedFilteredTodoList::new(
+            list,
+            TodoListFilter::default(),
+            vec![SortField::File.ascending()],
+            Grouping::Project,
+            UrgencyCoefficients::default(),
+        );
+        let lines = |todo_list: &SortedFilteredTodoList| {
+            todo_list
+                .list()
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        todo_list.select_index(0);
+        assert!(todo_list.move_selected(1));
+        assert_eq!(
+            lines(&todo_list),
+            [
+                "2024-01-01 Third +x",
+                "2024-01-01 Second +y",
+                "2024-01-01 First +x"
+            ]
+        );
+        assert_eq!(todo_list.selected_index(), Some(2));
+        assert!(!todo_list.move_selected(1));
+        assert!(todo_list.move_selected(-1));
+        assert_eq!(todo_list.selected_index(), Some(0));
+        assert!(!todo_list.move_selected(-1));
+    }
+}
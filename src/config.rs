@@ -1,14 +1,28 @@
-use std::fmt::Debug;
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Write},
+    path::{Path, PathBuf},
+};
 
-use chrono::NaiveDate;
+use anyhow::Context;
+use chrono::{format::StrftimeItems, Datelike, NaiveDate, TimeDelta, Weekday};
 use crokey::{key, KeyCombination};
 use ratatui::{
     style::{Style, Stylize},
-    text::Span,
-    widgets::Block,
+    text::{Line, Span},
+    widgets::{Block, Borders},
 };
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    todo::{Recurring, RecurringUnit, TodoItem},
+    urgency::UrgencySource,
+    view::{
+        AgendaSection, BatchPrompt, BoardColumns, DateKind, DayCount, DueBucket, Group, Grouping,
+        SortField, SortKey,
+    },
+};
+
 macro_rules! config_struct {
     (
         $name:ident:
@@ -20,7 +34,8 @@ macro_rules! config_struct {
         ),+
         $( , )?
     ) => {
-        #[derive(Debug, Deserialize, Serialize)]
+        #[derive(Debug, Clone, Deserialize, Serialize)]
+        #[serde(default)]
         pub struct $name {
             $(
                 $(
@@ -48,10 +63,30 @@ macro_rules! config_struct {
     };
 }
 
+mod theme;
+
+pub use theme::Theme;
+use theme::ThemeFile;
+
 config_struct! {
     Config:
     ui: UI,
     pub keys: Keys,
+    pub filter: Filter,
+    pub sort: Sort,
+    pub urgency: UrgencyCoefficients,
+    pub agenda: Agenda,
+    pub board: Board,
+    pub stats: Stats,
+    pub reminders: Reminders,
+    /// Styles of the active theme
+    #[serde(skip)]
+    styles: Styles,
+    /// Names and styles of the loaded themes
+    #[serde(skip)]
+    themes: Vec<(String, Styles)>,
+    #[serde(skip)]
+    theme_index: usize,
 }
 
 config_struct! {
@@ -67,6 +102,386 @@ config_struct! {
     pub priority: KeyCombination = key!(ctrl-p),
     pub completion: KeyCombination = key!(ctrl-d),
     pub t: KeyCombination = key!(ctrl-t),
+    pub due: KeyCombination = key!(ctrl-o),
+    pub hidden: KeyCombination = key!(ctrl-r),
+    pub word_logic: KeyCombination = key!(ctrl-l),
+    pub word_match: KeyCombination = key!(ctrl-g),
+    pub sidebar: KeyCombination = key!(s),
+    pub select: KeyCombination = key!(space),
+    pub search: KeyCombination = key!('?'),
+    pub search_next: KeyCombination = key!(n),
+    pub search_previous: KeyCombination = key!(shift-n),
+    pub cycle_sort: KeyCombination = key!(o),
+    pub edit_sort: KeyCombination = key!(shift-o),
+    pub cycle_group: KeyCombination = key!(ctrl-g),
+    pub fold: KeyCombination = key!(z),
+    pub unfold_all: KeyCombination = key!(shift-z),
+    pub move_up: KeyCombination = key!(shift-k),
+    pub move_down: KeyCombination = key!(shift-j),
+    pub save: KeyCombination = key!(ctrl-s),
+    pub details: KeyCombination = key!(i),
+    pub toggle_completion: KeyCombination = key!(x),
+    pub help: KeyCombination = key!(F1),
+    pub calendar: KeyCombination = key!(c),
+    pub previous_month: KeyCombination = key!(shift-h),
+    pub next_month: KeyCombination = key!(shift-l),
+    pub agenda: KeyCombination = key!(a),
+    pub postpone: KeyCombination = key!(p),
+    pub reschedule: KeyCombination = key!(r),
+    pub board: KeyCombination = key!(b),
+    pub move_left: KeyCombination = key!(shift-h),
+    pub move_right: KeyCombination = key!(shift-l),
+    pub page_up: KeyCombination = key!(pageup),
+    pub page_down: KeyCombination = key!(pagedown),
+    pub half_page_up: KeyCombination = key!(ctrl-u),
+    pub half_page_down: KeyCombination = key!(ctrl-d),
+    /// Pressed twice to select the first item, or the item given by a count prefix
+    pub top: KeyCombination = key!(g),
+    /// Selects the last item, or the item given by a count prefix
+    pub bottom: KeyCombination = key!(shift-g),
+    pub mark: KeyCombination = key!(m),
+    pub visual: KeyCombination = key!(v),
+    pub undo: KeyCombination = key!(u),
+    pub set_priority: KeyCombination = key!(shift-p),
+    pub add_category: KeyCombination = key!('+'),
+    pub remove_category: KeyCombination = key!('-'),
+    pub archive: KeyCombination = key!(shift-a),
+    pub delete: KeyCombination = key!(shift-d),
+    pub cycle_theme: KeyCombination = key!(shift-t),
+    pub stats: KeyCombination = key!(shift-s),
+    pub stats_period: KeyCombination = key!(w),
+}
+
+impl Keys {
+    /// Returns the key bindings with a short description, grouped by mode
+    pub fn help_sections(&self) -> Vec<(&'static str, Vec<(KeyCombination, &'static str)>)> {
+        vec![
+            (
+                "List",
+                vec![
+                    (self.up, "Select previous item"),
+                    (self.down, "Select next item"),
+                    (self.page_up, "Page up"),
+                    (self.page_down, "Page down"),
+                    (self.half_page_up, "Half page up"),
+                    (self.half_page_down, "Half page down"),
+                    (self.top, "Select first item, press twice"),
+                    (self.bottom, "Select last item"),
+                    (self.toggle_completion, "Toggle completion"),
+                    (self.mark, "Toggle mark"),
+                    (self.visual, "Mark range"),
+                    (self.cancel, "Clear marks"),
+                    (self.set_priority, "Set priority"),
+                    (self.add_category, "Add projects or contexts"),
+                    (self.remove_category, "Remove projects or contexts"),
+                    (self.postpone, "Postpone due date by a day"),
+                    (self.archive, "Archive"),
+                    (self.delete, "Delete"),
+                    (self.undo, "Undo last change"),
+                    (self.move_up, "Move item up"),
+                    (self.move_down, "Move item down"),
+                    (self.details, "Toggle details"),
+                    (self.focus_filter, "Edit filter"),
+                    (self.sidebar, "Open sidebar"),
+                    (self.calendar, "Open calendar"),
+                    (self.agenda, "Open agenda"),
+                    (self.board, "Open board"),
+                    (self.stats, "Open statistics"),
+                    (self.search, "Search"),
+                    (self.search_next, "Next search match"),
+                    (self.search_previous, "Previous search match"),
+                    (self.cycle_sort, "Cycle sort presets"),
+                    (self.edit_sort, "Edit sort order"),
+                    (self.cycle_group, "Cycle grouping"),
+                    (self.fold, "Fold or unfold group"),
+                    (self.unfold_all, "Unfold all groups"),
+                    (self.save, "Save todo file"),
+                    (self.cycle_theme, "Cycle theme"),
+                    (self.help, "Show help"),
+                    (self.quit, "Quit"),
+                ],
+            ),
+            (
+                "Visual",
+                vec![
+                    (self.visual, "Keep marks"),
+                    (self.confirm, "Keep marks"),
+                    (self.cancel, "Restore previous marks"),
+                ],
+            ),
+            (
+                "Batch",
+                vec![
+                    (self.confirm, "Apply to marked or selected items"),
+                    (self.cancel, "Back to list"),
+                ],
+            ),
+            (
+                "Filter",
+                vec![
+                    (self.completion, "Cycle completion filter"),
+                    (self.priority, "Cycle priority filter"),
+                    (self.t, "Toggle threshold filter"),
+                    (self.hidden, "Toggle hidden items"),
+                    (self.due, "Cycle due filter"),
+                    (self.word_logic, "Toggle AND/OR of words"),
+                    (self.word_match, "Cycle word matching"),
+                    (self.confirm, "Back to list"),
+                    (self.cancel, "Back to list"),
+                ],
+            ),
+            (
+                "Sidebar",
+                vec![
+                    (self.up, "Select previous category"),
+                    (self.down, "Select next category"),
+                    (self.select, "Toggle category filter"),
+                    (self.sidebar, "Close sidebar"),
+                    (self.cancel, "Back to list"),
+                ],
+            ),
+            (
+                "Calendar",
+                vec![
+                    (self.left, "Previous day"),
+                    (self.right, "Next day"),
+                    (self.up, "Previous week"),
+                    (self.down, "Next week"),
+                    (self.previous_month, "Previous month"),
+                    (self.next_month, "Next month"),
+                    (self.calendar, "Close calendar"),
+                    (self.cancel, "Back to list"),
+                ],
+            ),
+            (
+                "Board",
+                vec![
+                    (self.left, "Focus previous column"),
+                    (self.right, "Focus next column"),
+                    (self.up, "Select previous card"),
+                    (self.down, "Select next card"),
+                    (self.move_left, "Move card to previous column"),
+                    (self.move_right, "Move card to next column"),
+                    (self.toggle_completion, "Toggle completion"),
+                    (self.board, "Back to list"),
+                    (self.cancel, "Back to list"),
+                ],
+            ),
+            (
+                "Stats",
+                vec![
+                    (self.stats_period, "Toggle days and weeks"),
+                    (self.stats, "Back to list"),
+                    (self.cancel, "Back to list"),
+                ],
+            ),
+            (
+                "Agenda",
+                vec![
+                    (self.up, "Select previous item"),
+                    (self.down, "Select next item"),
+                    (self.toggle_completion, "Toggle completion"),
+                    (self.postpone, "Postpone by a day"),
+                    (self.reschedule, "Enter new date"),
+                    (self.agenda, "Back to list"),
+                    (self.cancel, "Back to list"),
+                ],
+            ),
+            (
+                "Reschedule",
+                vec![
+                    (self.confirm, "Apply date, e.g. 2024-12-24, 3d, 2w or 1m"),
+                    (self.cancel, "Back to agenda"),
+                ],
+            ),
+            (
+                "Search",
+                vec![(self.confirm, "Keep search"), (self.cancel, "Clear search")],
+            ),
+            (
+                "Sort",
+                vec![
+                    (self.confirm, "Apply sort order"),
+                    (self.cancel, "Restore previous sort order"),
+                ],
+            ),
+            (
+                "Paste",
+                vec![
+                    (self.confirm, "Add the valid pasted items"),
+                    (self.cancel, "Discard the pasted items"),
+                    (self.up, "Scroll up"),
+                    (self.down, "Scroll down"),
+                ],
+            ),
+            (
+                "Reminders",
+                vec![
+                    (self.confirm, "Dismiss reminders"),
+                    (self.cancel, "Dismiss reminders"),
+                    (self.up, "Scroll up"),
+                    (self.down, "Scroll down"),
+                ],
+            ),
+            (
+                "Help",
+                vec![
+                    (self.up, "Scroll up"),
+                    (self.down, "Scroll down"),
+                    (self.cancel, "Close help"),
+                ],
+            ),
+        ]
+    }
+}
+
+config_struct! {
+    Filter:
+    /// Amount of days used by the "due within" filter state
+    pub due_within_days: u32 = 7,
+    /// Whether all or any of the filter words need to match
+    pub word_logic: WordLogic,
+    /// How filter words are matched against item content
+    pub word_match: WordMatch,
+}
+
+/// How multiple words of a filter input are combined
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordLogic {
+    /// Every word needs to match
+    #[default]
+    All,
+    /// At least one word needs to match
+    Any,
+}
+
+/// How a single filter word is matched against a content token
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordMatch {
+    /// The token starts with the word
+    Prefix,
+    /// The token equals the word
+    Exact,
+    /// The token contains the word
+    #[default]
+    Substring,
+}
+
+impl WordLogic {
+    pub fn toggled(self) -> Self {
+        match self {
+            WordLogic::All => WordLogic::Any,
+            WordLogic::Any => WordLogic::All,
+        }
+    }
+}
+
+impl WordMatch {
+    pub fn next(self) -> Self {
+        match self {
+            WordMatch::Prefix => WordMatch::Exact,
+            WordMatch::Exact => WordMatch::Substring,
+            WordMatch::Substring => WordMatch::Prefix,
+        }
+    }
+
+    /// Checks whether the lowercase token matches the lowercase word
+    pub fn matches(self, token: &str, word: &str) -> bool {
+        match self {
+            WordMatch::Prefix => token.starts_with(word),
+            WordMatch::Exact => token == word,
+            WordMatch::Substring => token.contains(word),
+        }
+    }
+}
+
+config_struct! {
+    Sort:
+    /// Sort order used on startup
+    pub order: Vec<SortKey> = default_sort_order(),
+    /// Grouping used on startup
+    pub group_by: Grouping,
+    /// Sort orders to cycle through at runtime
+    pub presets: Vec<Vec<SortKey>> = vec![
+        default_sort_order(),
+        vec![
+            SortField::Completion.ascending(),
+            SortField::Due.ascending(),
+            SortField::Priority.ascending(),
+        ],
+        vec![SortField::Project.ascending(), SortField::Priority.ascending()],
+        vec![SortField::Created.descending()],
+        vec![SortField::File.ascending()],
+        vec![SortField::Completion.ascending(), SortField::Urgency.descending()],
+    ],
+}
+
+config_struct! {
+    Agenda:
+    /// Amount of days after today shown in the agenda
+    pub days: u32 = 7,
+    /// List open items without due and threshold date at the bottom of the agenda
+    pub undated: bool = false,
+}
+
+config_struct! {
+    Reminders:
+    /// Show a popup when the date changes and open items become due or reach their threshold date
+    pub enabled: bool = true,
+    /// Ring the terminal bell along with the popup
+    pub bell: bool = false,
+}
+
+config_struct! {
+    Stats:
+    /// Amount of days up to today shown in the daily charts
+    pub days: usize = 60,
+    /// Amount of weeks up to the current one shown in the weekly charts
+    pub weeks: usize = 12,
+}
+
+config_struct! {
+    Board:
+    /// Item property the columns are defined by
+    pub column_by: BoardColumns,
+    /// Column values in order, items matching none are placed in the first column
+    pub columns: Vec<String> = ["todo", "doing", "waiting", "done"].map(str::to_owned).to_vec(),
+}
+
+config_struct! {
+    UrgencyCoefficients:
+    /// Weight of the due date, fully applied when overdue for a week
+    pub due: f64 = 12.0,
+    /// Weights of priority letters
+    pub priorities: BTreeMap<char, f64> = BTreeMap::from([('A', 6.0), ('B', 3.9), ('C', 1.8)]),
+    /// Weight of the item age, fully applied after `age_max_days`
+    pub age: f64 = 2.0,
+    pub age_max_days: u32 = 365,
+    /// Weight of a threshold date that has not been reached yet
+    pub threshold: f64 = -5.0,
+    /// Weights of projects
+    pub projects: BTreeMap<String, f64>,
+    /// Weights of contexts
+    pub contexts: BTreeMap<String, f64>,
+}
+
+/// Where the detail pane is placed relative to the list
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetailsPosition {
+    #[default]
+    Right,
+    Bottom,
+}
+
+fn default_sort_order() -> Vec<SortKey> {
+    vec![
+        SortField::Completion.ascending(),
+        SortField::Priority.ascending(),
+        SortField::Created.ascending(),
+    ]
 }
 
 config_struct! {
@@ -77,13 +492,190 @@ config_struct! {
     item_incomplete_mark: String = "[ ]".to_owned(),
     item_priority_mark_format: String = "({p})".to_owned(),
     item_no_priority_mark: String = "".to_owned(),
+    // -- Dates --
+    /// Format of absolute dates, see chrono's strftime specifiers
+    date_format: String = "%d.%m.%Y".to_owned(),
+    /// Show due and threshold dates relative to today
+    relative_dates: bool = false,
+    date_today: String = "today".to_owned(),
+    date_tomorrow: String = "tomorrow".to_owned(),
+    /// Format of dates within the coming week
+    date_weekday_format: String = "%A".to_owned(),
+    date_future_format: String = "in {n}d".to_owned(),
+    date_overdue_format: String = "{n}d overdue".to_owned(),
+    date_past_format: String = "{n}d ago".to_owned(),
+    /// Due dates within this amount of days are styled as due soon
+    due_soon_days: u32 = 3,
+    /// Threshold dates passed within this amount of days are styled as recently reached
+    t_recent_days: u32 = 3,
+    /// Tint the whole row of overdue items
+    overdue_row_tint: bool = false,
     // -- Filter --
     filter_completion_disabled: String = "[*]".to_owned(),
     filter_priority_disabled: String = "(*)".to_owned(),
     filter_t_enabled: String = "t".to_owned(),
     filter_t_disabled: String = "t".to_owned(),
-    /// Styles
-    styles: Styles,
+    filter_hidden_enabled: String = "h".to_owned(),
+    filter_hidden_disabled: String = "h".to_owned(),
+    filter_due_disabled: String = "due".to_owned(),
+    filter_due_overdue: String = "overdue".to_owned(),
+    filter_due_today: String = "today".to_owned(),
+    filter_due_within_format: String = "{n}d".to_owned(),
+    filter_due_none: String = "no due".to_owned(),
+    filter_words_all: String = "AND".to_owned(),
+    filter_words_any: String = "OR".to_owned(),
+    filter_match_prefix: String = "w*".to_owned(),
+    filter_match_exact: String = "=w".to_owned(),
+    filter_match_substring: String = "*w*".to_owned(),
+    // -- Sidebar --
+    sidebar_width: u16 = 28,
+    sidebar_selected_mark: String = "*".to_owned(),
+    sidebar_unselected_mark: String = " ".to_owned(),
+    sidebar_count_format: String = "{open}/{total}".to_owned(),
+    // -- Calendar --
+    /// Format of the month in the calendar title, see chrono's strftime specifiers
+    calendar_month_format: String = " %B %Y ".to_owned(),
+    /// Shown below days with items, supports {due} and {t}
+    calendar_count_format: String = "{due}/{t}".to_owned(),
+    // -- Board --
+    /// Title of the board columns, supports {column} and {count}
+    board_title_format: String = " {column} ({count}) ".to_owned(),
+    /// Column name shown for an empty column value
+    board_empty_column: String = "none".to_owned(),
+    // -- Agenda --
+    agenda_overdue: String = "Overdue".to_owned(),
+    agenda_undated: String = "Undated".to_owned(),
+    /// Format of the day headings, see chrono's strftime specifiers
+    agenda_day_format: String = "%A, %d.%m.%Y".to_owned(),
+    reschedule_prompt: String = "date: ".to_owned(),
+    // -- Batch --
+    priority_prompt: String = "priority: ".to_owned(),
+    add_category_prompt: String = "add: ".to_owned(),
+    remove_category_prompt: String = "remove: ".to_owned(),
+    // -- Search --
+    search_prompt: String = "?".to_owned(),
+    // -- Sort --
+    sort_prefix: String = "sort: ".to_owned(),
+    sort_prompt: String = "sort: ".to_owned(),
+    // -- Groups --
+    group_prefix: String = " group: ".to_owned(),
+    group_expanded_mark: String = "v".to_owned(),
+    group_collapsed_mark: String = ">".to_owned(),
+    group_count_format: String = " ({count})".to_owned(),
+    group_no_project: String = "No project".to_owned(),
+    group_no_context: String = "No context".to_owned(),
+    group_no_priority: String = "No priority".to_owned(),
+    group_no_due: String = "No due date".to_owned(),
+    group_overdue: String = "Overdue".to_owned(),
+    group_today: String = "Today".to_owned(),
+    group_this_week: String = "This week".to_owned(),
+    group_later: String = "Later".to_owned(),
+    /// Capture mouse events, disabling the terminal's text selection
+    mouse: bool = true,
+    // -- Navigation --
+    /// Moving the selection past the first or last item wraps around
+    wrap_selection: bool = true,
+    /// Rows kept visible above and below the selection
+    scroll_padding: usize = 2,
+    // -- Urgency --
+    urgency_column: bool = false,
+    // -- Details --
+    details_position: DetailsPosition,
+    /// Width or height of the detail pane, depending on its position
+    details_size: u16 = 48,
+    details_status_label: String = "Status:".to_owned(),
+    details_priority_label: String = "Priority:".to_owned(),
+    details_created_label: String = "Created:".to_owned(),
+    details_completed_label: String = "Completed:".to_owned(),
+    details_due_label: String = "Due:".to_owned(),
+    details_threshold_label: String = "Threshold:".to_owned(),
+    details_recurrence_label: String = "Recurrence:".to_owned(),
+    details_hidden_label: String = "Hidden:".to_owned(),
+    details_projects_label: String = "Projects:".to_owned(),
+    details_contexts_label: String = "Contexts:".to_owned(),
+    details_urgency_label: String = "Urgency:".to_owned(),
+    details_raw_label: String = "Raw:".to_owned(),
+    details_status_open: String = "open".to_owned(),
+    details_status_completed: String = "completed".to_owned(),
+    details_hidden_value: String = "yes".to_owned(),
+    /// Description of the recurrence, supports {every} and {from}
+    details_recurrence_format: String = "{every}, {from}".to_owned(),
+    details_every_day: String = "every day".to_owned(),
+    /// Recurrence of more than one day, supports {n}
+    details_every_days_format: String = "every {n} days".to_owned(),
+    details_every_week: String = "every week".to_owned(),
+    /// Recurrence of more than one week, supports {n}
+    details_every_weeks_format: String = "every {n} weeks".to_owned(),
+    details_every_month: String = "every month".to_owned(),
+    /// Recurrence of more than one month, supports {n}
+    details_every_months_format: String = "every {n} months".to_owned(),
+    details_every_year: String = "every year".to_owned(),
+    /// Recurrence of more than one year, supports {n}
+    details_every_years_format: String = "every {n} years".to_owned(),
+    details_from_due: String = "from the due date".to_owned(),
+    details_from_completion: String = "from completion".to_owned(),
+    details_urgency_due_today: String = "due today".to_owned(),
+    /// Urgency from an upcoming due date, supports {n}
+    details_urgency_due_format: String = "due in {n}d".to_owned(),
+    /// Urgency from an overdue due date, supports {n}
+    details_urgency_overdue_format: String = "overdue {n}d".to_owned(),
+    /// Urgency from the priority, supports {p}
+    details_urgency_priority_format: String = "priority {p}".to_owned(),
+    /// Urgency from the age, supports {n}
+    details_urgency_age_format: String = "age {n}d".to_owned(),
+    details_urgency_threshold: String = "threshold ahead".to_owned(),
+    // -- Compact --
+    /// Terminal width below which the compact layout is used
+    compact_width: u16 = 80,
+    /// Terminal height below which the compact layout is used
+    compact_height: u16 = 20,
+    /// Status bar content of the compact layout, supports the same placeholders as `status_format`
+    compact_status_format: String = "{mode}{dirty} {visible}/{total}{marked}".to_owned(),
+    // -- Help --
+    help_title: String = " Help ".to_owned(),
+    help_width: u16 = 60,
+    // -- Stats --
+    /// Title of the completion chart, supports {period} and {max}
+    stats_completed_title: String = " Completed per {period} (max {max}) ".to_owned(),
+    /// Title of the overdue chart, supports {period} and {max}
+    stats_overdue_title: String = " Overdue per {period} (max {max}) ".to_owned(),
+    stats_open_title: String = " Open by project and priority ".to_owned(),
+    stats_summary_title: String = " Summary ".to_owned(),
+    stats_no_project: String = "no project".to_owned(),
+    stats_no_priority: String = "-".to_owned(),
+    stats_total_header: String = "total".to_owned(),
+    stats_open_label: String = "Open:".to_owned(),
+    stats_overdue_label: String = "Overdue:".to_owned(),
+    stats_completed_label: String = "Completed:".to_owned(),
+    stats_average_label: String = "Days to completion:".to_owned(),
+    /// Label of the weekly bars, formatted from the first day of the week
+    stats_week_format: String = "W%V".to_owned(),
+    // -- Reminders --
+    reminder_title: String = " Reminders ".to_owned(),
+    reminder_width: u16 = 60,
+    reminder_due: String = "due".to_owned(),
+    reminder_t: String = "starts".to_owned(),
+    // -- Paste --
+    /// Title of the pasted items preview, supports {valid} and {invalid}
+    paste_title_format: String = " Paste: {valid} new, {invalid} invalid ".to_owned(),
+    paste_width: u16 = 80,
+    // -- Status --
+    /// Status bar content, supports {mode}, {file}, {dirty}, {visible}, {total}, {completed} and {marked}
+    status_format: String = "{mode}  {file}{dirty}  {visible}/{total} shown, {completed} done{marked}"
+        .to_owned(),
+    status_dirty: String = " [+]".to_owned(),
+    /// Replaces {marked} if items are marked, supports {n}
+    status_marked_format: String = "  {n} marked".to_owned(),
+    status_saved: String = "".to_owned(),
+    /// Amount of ticks a status message stays visible
+    status_message_ticks: u32 = 12,
+    // -- Theme --
+    /// Bundled theme: dark, light, solarized, high_contrast or monochrome
+    theme: Theme,
+    /// TOML file with a `base` theme and `[styles]` replacing its styles, used instead of `theme`
+    theme_file: Option<PathBuf>,
+    /// Styles replacing the ones of every theme
+    styles: toml::Table,
 }
 
 config_struct! {
@@ -95,34 +687,177 @@ config_struct! {
     item_space: Style,
     item_context: Style = Style::new().green().bold(),
     item_project: Style = Style::new().cyan().bold(),
-    item_due: Style = Style::new().red(),
+    item_due: Style,
+    item_due_soon: Style = Style::new().yellow(),
+    item_due_today: Style = Style::new().red(),
+    item_due_overdue: Style = Style::new().red().bold(),
     item_t: Style = Style::new().blue(),
+    item_t_recent: Style = Style::new().magenta().bold(),
+    item_t_past: Style = Style::new().gray(),
+    item_overdue_row: Style = Style::new().red(),
+    item_urgency: Style = Style::new().yellow(),
     item_selected: Style = Style::new().bold(),
+    item_marked: Style = Style::new().on_dark_gray(),
     // -- Filter --
     filter_disabled: Style = Style::new().gray(),
+    // -- Sidebar --
+    sidebar_count: Style = Style::new().gray(),
+    // -- Calendar --
+    calendar_weekday: Style = Style::new().gray(),
+    calendar_today: Style = Style::new().underlined(),
+    calendar_selected: Style = Style::new().reversed(),
+    calendar_count: Style = Style::new().yellow(),
+    // -- Board --
+    board_focused_title: Style = Style::new().bold(),
+    // -- Search --
+    search_match: Style = Style::new().black().on_yellow(),
+    // -- Sort --
+    sort: Style = Style::new().gray(),
+    // -- Groups --
+    group_header: Style = Style::new().bold(),
+    // -- Details --
+    details_label: Style = Style::new().bold(),
+    // -- Help --
+    help_section: Style = Style::new().bold().underlined(),
+    help_key: Style = Style::new().yellow(),
+    // -- Stats --
+    stats_completed: Style = Style::new().green(),
+    stats_overdue: Style = Style::new().red(),
+    stats_header: Style = Style::new().bold(),
+    // -- Paste --
+    paste_invalid: Style = Style::new().gray(),
+    paste_error: Style = Style::new().red(),
+    // -- Status --
+    status: Style = Style::new().gray(),
+    status_mode: Style = Style::new().bold(),
+    status_dirty: Style = Style::new().yellow(),
+    status_message: Style,
+    status_error: Style = Style::new().red(),
+    // -- Input --
+    input_error: Style = Style::new().red(),
 }
 
 impl Config {
-    pub fn default_block(&self) -> Block {
-        Block::bordered().border_style(self.ui.styles.border)
+    /// Loads the bundled themes and the theme file, with paths relative to `dir`,
+    /// and activates the configured one. If `NO_COLOR` is set, only the monochrome theme
+    /// is available and colors of the style overrides are ignored.
+    pub fn load_themes(&mut self, dir: &Path) -> anyhow::Result<()> {
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            let theme = Theme::Monochrome;
+            let styles = theme
+                .styles()
+                .patched(&theme::without_colors(&self.ui.styles))
+                .context("Invalid styles")?;
+            self.styles = styles.clone();
+            self.themes = vec![(theme.name().to_owned(), styles)];
+            self.theme_index = 0;
+            return Ok(());
+        }
+
+        let mut themes = Theme::ALL
+            .into_iter()
+            .map(|theme| {
+                Ok((
+                    theme.name().to_owned(),
+                    theme.styles().patched(&self.ui.styles)?,
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context("Invalid styles")?;
+        let mut index = Theme::ALL
+            .iter()
+            .position(|theme| *theme == self.ui.theme)
+            .unwrap_or_default();
+
+        if let Some(file) = &self.ui.theme_file {
+            let path = dir.join(file);
+            let theme_file: ThemeFile = toml::from_str(
+                &std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read theme file {}", path.display()))?,
+            )?;
+            let styles = theme_file
+                .base
+                .styles()
+                .patched(&theme_file.styles)
+                .and_then(|styles| styles.patched(&self.ui.styles))
+                .with_context(|| format!("Invalid styles in theme file {}", path.display()))?;
+            let name = path
+                .file_stem()
+                .map_or("file".into(), |name| name.to_string_lossy());
+            index = themes.len();
+            themes.push((name.into_owned(), styles));
+        }
+
+        self.styles = themes[index].1.clone();
+        self.themes = themes;
+        self.theme_index = index;
+        Ok(())
+    }
+
+    /// Checks that the configured date formats are valid strftime strings for dates,
+    /// which would otherwise panic while rendering
+    pub fn validate_date_formats(&self) -> anyhow::Result<()> {
+        let date = NaiveDate::default();
+        [
+            ("date_format", &self.ui.date_format),
+            ("date_weekday_format", &self.ui.date_weekday_format),
+            ("calendar_month_format", &self.ui.calendar_month_format),
+            ("agenda_day_format", &self.ui.agenda_day_format),
+            ("stats_week_format", &self.ui.stats_week_format),
+        ]
+        .into_iter()
+        .try_for_each(|(name, format)| {
+            let items = StrftimeItems::new(format)
+                .parse()
+                .with_context(|| format!("Invalid {name} {format:?}"))?;
+            write!(String::new(), "{}", date.format_with_items(items.iter()))
+                .with_context(|| format!("Invalid {name} {format:?}, only dates can be formatted"))
+        })
+    }
+
+    /// Activates the next loaded theme, returning its name
+    pub fn cycle_theme(&mut self) -> Option<&str> {
+        if self.themes.is_empty() {
+            return None;
+        }
+        self.theme_index = (self.theme_index + 1) % self.themes.len();
+        let (name, styles) = &self.themes[self.theme_index];
+        self.styles = styles.clone();
+        Some(name)
     }
 
-    pub fn item_selection_mark(&self) -> Span {
+    pub fn default_block(&self) -> Block<'_> {
+        Block::bordered().border_style(self.styles.border)
+    }
+
+    /// Block of the compact layout, only keeping the top border for the title
+    pub fn compact_block(&self) -> Block<'_> {
+        Block::new()
+            .borders(Borders::TOP)
+            .border_style(self.styles.border)
+    }
+
+    /// Returns whether the compact layout is used for the terminal size
+    pub fn compact(&self, width: u16, height: u16) -> bool {
+        width < self.ui.compact_width || height < self.ui.compact_height
+    }
+
+    pub fn item_selection_mark(&self) -> Span<'_> {
         Span::from(&self.ui.item_selection_mark)
     }
 
-    pub fn item_complete_mark(&self) -> Span {
+    pub fn item_complete_mark(&self) -> Span<'_> {
         Span::from(&self.ui.item_complete_mark)
     }
 
-    pub fn item_incomplete_mark(&self) -> Span {
+    pub fn item_incomplete_mark(&self) -> Span<'_> {
         Span::from(&self.ui.item_incomplete_mark)
     }
 
-    pub fn filter_completion_disabled(&self) -> Span {
+    pub fn filter_completion_disabled(&self) -> Span<'_> {
         Span::styled(
             &self.ui.filter_completion_disabled,
-            self.ui.styles.filter_disabled,
+            self.styles.filter_disabled,
         )
     }
 
@@ -133,21 +868,21 @@ impl Config {
             .max(self.filter_completion_disabled().width())
     }
 
-    pub fn item_priority_mark(&self, prio: char) -> Span {
+    pub fn item_priority_mark(&self, prio: char) -> Span<'_> {
         Span::from(
             self.ui.item_priority_mark_format
                 .replacen("{p}", &prio.to_string(), 1),
         )
     }
 
-    pub fn item_no_priority_mark(&self) -> Span {
+    pub fn item_no_priority_mark(&self) -> Span<'_> {
         Span::from(&self.ui.item_no_priority_mark)
     }
 
-    pub fn filter_priority_disabled(&self) -> Span {
+    pub fn filter_priority_disabled(&self) -> Span<'_> {
         Span::styled(
             &self.ui.filter_priority_disabled,
-            self.ui.styles.filter_disabled,
+            self.styles.filter_disabled,
         )
     }
 
@@ -159,41 +894,87 @@ impl Config {
     }
 
     pub fn item_word<'a>(&'a self, word: &'a str) -> Span<'a> {
-        Span::styled(word, self.ui.styles.item_word)
+        Span::styled(word, self.styles.item_word)
     }
 
     pub fn item_space<'a>(&'a self, space: &'a str) -> Span<'a> {
-        Span::styled(space, self.ui.styles.item_space)
+        Span::styled(space, self.styles.item_space)
     }
 
     pub fn item_context<'a>(&'a self, context: &'a str) -> Span<'a> {
-        Span::styled(context, self.ui.styles.item_context)
+        Span::styled(context, self.styles.item_context)
     }
 
     pub fn item_project<'a>(&'a self, project: &'a str) -> Span<'a> {
-        Span::styled(project, self.ui.styles.item_project)
+        Span::styled(project, self.styles.item_project)
+    }
+
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(&self.ui.date_format).to_string()
+    }
+
+    pub fn relative_dates(&self) -> bool {
+        self.ui.relative_dates
     }
 
-    pub fn item_due_date(&self, date: NaiveDate) -> Span {
+    /// Formats the date relative to today if enabled, using `past_format` for past dates
+    fn format_item_date(&self, date: NaiveDate, today: NaiveDate, past_format: &str) -> String {
+        if !self.ui.relative_dates {
+            return self.format_date(date);
+        }
+
+        match (date - today).num_days() {
+            0 => self.ui.date_today.clone(),
+            1 => self.ui.date_tomorrow.clone(),
+            2..=6 => date.format(&self.ui.date_weekday_format).to_string(),
+            days if days > 6 => self
+                .ui
+                .date_future_format
+                .replacen("{n}", &days.to_string(), 1),
+            days => past_format.replacen("{n}", &(-days).to_string(), 1),
+        }
+    }
+
+    pub fn item_due_date(&self, date: NaiveDate, today: NaiveDate) -> Span<'_> {
+        let style = match (date - today).num_days() {
+            days if days < 0 => self.styles.item_due_overdue,
+            0 => self.styles.item_due_today,
+            days if days <= self.ui.due_soon_days as i64 => self.styles.item_due_soon,
+            _ => self.styles.item_due,
+        };
         Span::styled(
-            date.format("%d.%m.%Y").to_string(),
-            self.ui.styles.item_due,
+            self.format_item_date(date, today, &self.ui.date_overdue_format),
+            style,
         )
     }
 
-    pub fn item_t_date(&self, date: NaiveDate) -> Span {
+    pub fn item_t_date(&self, date: NaiveDate, today: NaiveDate) -> Span<'_> {
+        let style = match (today - date).num_days() {
+            days if days < 0 => self.styles.item_t,
+            days if days <= self.ui.t_recent_days as i64 => self.styles.item_t_recent,
+            _ => self.styles.item_t_past,
+        };
         Span::styled(
-            date.format("%d.%m.%Y").to_string(),
-            self.ui.styles.item_t,
+            self.format_item_date(date, today, &self.ui.date_past_format),
+            style,
         )
     }
 
-    pub fn filter_t_enabled(&self) -> Span {
-        Span::styled(&self.ui.filter_t_enabled, self.ui.styles.item_t)
+    /// Returns the style of the whole item row
+    pub fn item_row_style(&self, item: &TodoItem, today: NaiveDate) -> Style {
+        let overdue = item.completion_date.is_none() && item.due.is_some_and(|due| due < today);
+        match self.ui.overdue_row_tint && overdue {
+            true => self.styles.item_overdue_row,
+            false => Style::new(),
+        }
+    }
+
+    pub fn filter_t_enabled(&self) -> Span<'_> {
+        Span::styled(&self.ui.filter_t_enabled, self.styles.item_t)
     }
 
-    pub fn filter_t_disabled(&self) -> Span {
-        Span::styled(&self.ui.filter_t_disabled, self.ui.styles.filter_disabled)
+    pub fn filter_t_disabled(&self) -> Span<'_> {
+        Span::styled(&self.ui.filter_t_disabled, self.styles.filter_disabled)
     }
 
     pub fn t_width(&self) -> usize {
@@ -202,7 +983,612 @@ impl Config {
             .max(self.filter_t_disabled().width())
     }
 
+    pub fn filter_hidden_enabled(&self) -> Span<'_> {
+        Span::from(&self.ui.filter_hidden_enabled)
+    }
+
+    pub fn filter_hidden_disabled(&self) -> Span<'_> {
+        Span::styled(&self.ui.filter_hidden_disabled, self.styles.filter_disabled)
+    }
+
+    pub fn hidden_width(&self) -> usize {
+        self.filter_hidden_enabled()
+            .width()
+            .max(self.filter_hidden_disabled().width())
+    }
+
+    pub fn filter_due_disabled(&self) -> Span<'_> {
+        Span::styled(&self.ui.filter_due_disabled, self.styles.filter_disabled)
+    }
+
+    pub fn filter_due_overdue(&self) -> Span<'_> {
+        Span::styled(&self.ui.filter_due_overdue, self.styles.item_due_overdue)
+    }
+
+    pub fn filter_due_today(&self) -> Span<'_> {
+        Span::styled(&self.ui.filter_due_today, self.styles.item_due_today)
+    }
+
+    pub fn filter_due_within(&self, days: u32) -> Span<'_> {
+        Span::styled(
+            self.ui
+                .filter_due_within_format
+                .replacen("{n}", &days.to_string(), 1),
+            self.styles.item_due_soon,
+        )
+    }
+
+    pub fn filter_due_none(&self) -> Span<'_> {
+        Span::styled(&self.ui.filter_due_none, self.styles.item_due)
+    }
+
+    pub fn due_width(&self) -> usize {
+        self.filter_due_disabled()
+            .width()
+            .max(self.filter_due_overdue().width())
+            .max(self.filter_due_today().width())
+            .max(self.filter_due_within(self.filter.due_within_days).width())
+            .max(self.filter_due_none().width())
+    }
+
+    pub fn filter_words_all(&self) -> Span<'_> {
+        Span::from(&self.ui.filter_words_all)
+    }
+
+    pub fn filter_words_any(&self) -> Span<'_> {
+        Span::from(&self.ui.filter_words_any)
+    }
+
+    pub fn word_logic_width(&self) -> usize {
+        self.filter_words_all()
+            .width()
+            .max(self.filter_words_any().width())
+    }
+
+    pub fn filter_match_prefix(&self) -> Span<'_> {
+        Span::from(&self.ui.filter_match_prefix)
+    }
+
+    pub fn filter_match_exact(&self) -> Span<'_> {
+        Span::from(&self.ui.filter_match_exact)
+    }
+
+    pub fn filter_match_substring(&self) -> Span<'_> {
+        Span::from(&self.ui.filter_match_substring)
+    }
+
+    pub fn word_match_width(&self) -> usize {
+        self.filter_match_prefix()
+            .width()
+            .max(self.filter_match_exact().width())
+            .max(self.filter_match_substring().width())
+    }
+
+    pub fn sidebar_width(&self) -> u16 {
+        self.ui.sidebar_width
+    }
+
+    pub fn sidebar_selected_mark(&self) -> Span<'_> {
+        Span::from(&self.ui.sidebar_selected_mark)
+    }
+
+    pub fn sidebar_unselected_mark(&self) -> Span<'_> {
+        Span::from(&self.ui.sidebar_unselected_mark)
+    }
+
+    pub fn sidebar_mark_width(&self) -> usize {
+        self.sidebar_selected_mark()
+            .width()
+            .max(self.sidebar_unselected_mark().width())
+    }
+
+    pub fn sidebar_project(&self, project: &str) -> Span<'_> {
+        Span::styled(format!("+{project}"), self.styles.item_project)
+    }
+
+    pub fn sidebar_context(&self, context: &str) -> Span<'_> {
+        Span::styled(format!("@{context}"), self.styles.item_context)
+    }
+
+    pub fn sidebar_count(&self, open: usize, total: usize) -> Span<'_> {
+        Span::styled(
+            self.ui
+                .sidebar_count_format
+                .replacen("{open}", &open.to_string(), 1)
+                .replacen("{total}", &total.to_string(), 1),
+            self.styles.sidebar_count,
+        )
+    }
+
+    pub fn calendar_month(&self, month: NaiveDate) -> Span<'_> {
+        Span::raw(month.format(&self.ui.calendar_month_format).to_string())
+    }
+
+    pub fn calendar_weekday(&self, weekday: Weekday) -> Span<'_> {
+        Span::styled(
+            weekday.to_string().chars().take(2).collect::<String>(),
+            self.styles.calendar_weekday,
+        )
+    }
+
+    pub fn calendar_day(&self, day: NaiveDate, today: bool, selected: bool) -> Span<'_> {
+        let mut style = Style::new();
+        if today {
+            style = style.patch(self.styles.calendar_today);
+        }
+        if selected {
+            style = style.patch(self.styles.calendar_selected);
+        }
+        Span::styled(day.day().to_string(), style)
+    }
+
+    pub fn calendar_count(&self, count: DayCount) -> Span<'_> {
+        Span::styled(
+            self.ui
+                .calendar_count_format
+                .replacen("{due}", &count.due.to_string(), 1)
+                .replacen("{t}", &count.t.to_string(), 1),
+            self.styles.calendar_count,
+        )
+    }
+
+    pub fn agenda_header(&self, section: AgendaSection, today: NaiveDate) -> Line<'_> {
+        let label = match section {
+            AgendaSection::Overdue => Span::from(&self.ui.agenda_overdue),
+            AgendaSection::Day(day) => {
+                let mut label = day.format(&self.ui.agenda_day_format).to_string();
+                if self.ui.relative_dates && day <= today + TimeDelta::days(1) {
+                    label = format!("{label} ({})", self.format_item_date(day, today, ""));
+                }
+                Span::raw(label)
+            }
+            AgendaSection::Undated => Span::from(&self.ui.agenda_undated),
+        };
+
+        Line::from(label).patch_style(self.styles.group_header)
+    }
+
+    pub fn board_title(&self, column: &str, count: usize, focused: bool) -> Span<'_> {
+        let column = match column {
+            "" => &self.ui.board_empty_column,
+            column => column,
+        };
+        let title = self
+            .ui
+            .board_title_format
+            .replacen("{column}", column, 1)
+            .replacen("{count}", &count.to_string(), 1);
+        match focused {
+            true => Span::styled(title, self.styles.board_focused_title),
+            false => Span::raw(title),
+        }
+    }
+
+    pub fn batch_prompt(&self, prompt: BatchPrompt) -> Span<'_> {
+        Span::from(match prompt {
+            BatchPrompt::SetPriority => &self.ui.priority_prompt,
+            BatchPrompt::AddCategories => &self.ui.add_category_prompt,
+            BatchPrompt::RemoveCategories => &self.ui.remove_category_prompt,
+        })
+    }
+
+    pub fn item_marked_style(&self) -> Style {
+        self.styles.item_marked
+    }
+
+    pub fn reschedule_prompt(&self) -> Span<'_> {
+        Span::from(&self.ui.reschedule_prompt)
+    }
+
+    pub fn search_prompt(&self) -> Span<'_> {
+        Span::from(&self.ui.search_prompt)
+    }
+
+    pub fn search_match_style(&self) -> Style {
+        self.styles.search_match
+    }
+
+    pub fn sort_order(&self, order: &[SortKey]) -> Span<'_> {
+        Span::styled(
+            format!(
+                "{prefix}{order}",
+                prefix = self.ui.sort_prefix,
+                order = SortKey::format_order(order)
+            ),
+            self.styles.sort,
+        )
+    }
+
+    pub fn grouping(&self, grouping: Grouping) -> Span<'_> {
+        let name = match grouping {
+            Grouping::None => return Span::raw(""),
+            Grouping::Project => "project",
+            Grouping::Context => "context",
+            Grouping::Priority => "priority",
+            Grouping::Due => "due",
+        };
+        Span::styled(
+            format!("{prefix}{name}", prefix = self.ui.group_prefix),
+            self.styles.sort,
+        )
+    }
+
+    pub fn group_fold_mark(&self, collapsed: bool) -> Span<'_> {
+        if collapsed {
+            Span::styled(&self.ui.group_collapsed_mark, self.styles.group_header)
+        } else {
+            Span::styled(&self.ui.group_expanded_mark, self.styles.group_header)
+        }
+    }
+
+    pub fn group_header(&self, group: &Group, grouping: Grouping, count: usize) -> Line<'_> {
+        let label = match group {
+            Group::Project(project) => self.sidebar_project(project),
+            Group::Context(context) => self.sidebar_context(context),
+            Group::Priority(priority) => self.item_priority_mark(*priority),
+            Group::Due(DueBucket::Overdue) => Span::from(&self.ui.group_overdue),
+            Group::Due(DueBucket::Today) => Span::from(&self.ui.group_today),
+            Group::Due(DueBucket::ThisWeek) => Span::from(&self.ui.group_this_week),
+            Group::Due(DueBucket::Later) => Span::from(&self.ui.group_later),
+            Group::Without => Span::from(match grouping {
+                Grouping::None => "",
+                Grouping::Project => &self.ui.group_no_project,
+                Grouping::Context => &self.ui.group_no_context,
+                Grouping::Priority => &self.ui.group_no_priority,
+                Grouping::Due => &self.ui.group_no_due,
+            }),
+        };
+        let count = Span::raw(self.ui.group_count_format.replacen(
+            "{count}",
+            &count.to_string(),
+            1,
+        ));
+
+        Line::from(vec![label, count]).patch_style(self.styles.group_header)
+    }
+
+    pub fn wrap_selection(&self) -> bool {
+        self.ui.wrap_selection
+    }
+
+    pub fn scroll_padding(&self) -> usize {
+        self.ui.scroll_padding
+    }
+
+    pub fn urgency_column(&self) -> bool {
+        self.ui.urgency_column
+    }
+
+    pub fn item_urgency(&self, urgency: f64) -> Span<'_> {
+        Span::styled(format!("{urgency:.1}"), self.styles.item_urgency)
+    }
+
+    pub fn mouse(&self) -> bool {
+        self.ui.mouse
+    }
+
+    pub fn details_position(&self) -> DetailsPosition {
+        self.ui.details_position
+    }
+
+    pub fn details_size(&self) -> u16 {
+        self.ui.details_size
+    }
+
+    pub fn details_status_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_status_label, self.styles.details_label)
+    }
+
+    pub fn details_priority_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_priority_label, self.styles.details_label)
+    }
+
+    pub fn details_created_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_created_label, self.styles.details_label)
+    }
+
+    pub fn details_completed_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_completed_label, self.styles.details_label)
+    }
+
+    pub fn details_due_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_due_label, self.styles.details_label)
+    }
+
+    pub fn details_threshold_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_threshold_label, self.styles.details_label)
+    }
+
+    pub fn details_recurrence_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_recurrence_label, self.styles.details_label)
+    }
+
+    pub fn details_hidden_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_hidden_label, self.styles.details_label)
+    }
+
+    pub fn details_projects_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_projects_label, self.styles.details_label)
+    }
+
+    pub fn details_contexts_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_contexts_label, self.styles.details_label)
+    }
+
+    pub fn details_urgency_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_urgency_label, self.styles.details_label)
+    }
+
+    pub fn details_raw_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.details_raw_label, self.styles.details_label)
+    }
+
+    pub fn details_status(&self, completed: bool) -> Span<'_> {
+        match completed {
+            true => Span::raw(&self.ui.details_status_completed),
+            false => Span::raw(&self.ui.details_status_open),
+        }
+    }
+
+    pub fn details_hidden_value(&self) -> Span<'_> {
+        Span::raw(&self.ui.details_hidden_value)
+    }
+
+    pub fn details_recurrence(&self, rec: Recurring) -> Span<'_> {
+        let (single, multiple) = match rec.unit() {
+            RecurringUnit::Days => (
+                &self.ui.details_every_day,
+                &self.ui.details_every_days_format,
+            ),
+            RecurringUnit::Weeks => (
+                &self.ui.details_every_week,
+                &self.ui.details_every_weeks_format,
+            ),
+            RecurringUnit::Months => (
+                &self.ui.details_every_month,
+                &self.ui.details_every_months_format,
+            ),
+            RecurringUnit::Years => (
+                &self.ui.details_every_year,
+                &self.ui.details_every_years_format,
+            ),
+        };
+        let every = match rec.amount() {
+            1 => single.clone(),
+            amount => multiple.replacen("{n}", &amount.to_string(), 1),
+        };
+        let from = match rec.relative() {
+            true => &self.ui.details_from_due,
+            false => &self.ui.details_from_completion,
+        };
+        Span::raw(
+            self.ui
+                .details_recurrence_format
+                .replacen("{every}", &every, 1)
+                .replacen("{from}", from, 1),
+        )
+    }
+
+    pub fn details_urgency_source(&self, source: &UrgencySource) -> String {
+        match source {
+            UrgencySource::Due(0) => self.ui.details_urgency_due_today.clone(),
+            UrgencySource::Due(days @ 1..) => {
+                self.ui
+                    .details_urgency_due_format
+                    .replacen("{n}", &days.to_string(), 1)
+            }
+            UrgencySource::Due(days) => {
+                self.ui
+                    .details_urgency_overdue_format
+                    .replacen("{n}", &(-days).to_string(), 1)
+            }
+            UrgencySource::Priority(priority) => {
+                self.ui
+                    .details_urgency_priority_format
+                    .replacen("{p}", &priority.to_string(), 1)
+            }
+            UrgencySource::Age(days) => {
+                self.ui
+                    .details_urgency_age_format
+                    .replacen("{n}", &days.to_string(), 1)
+            }
+            UrgencySource::Threshold => self.ui.details_urgency_threshold.clone(),
+            UrgencySource::Project(project) => format!("+{project}"),
+            UrgencySource::Context(context) => format!("@{context}"),
+        }
+    }
+
+    pub fn help_title(&self) -> Span<'_> {
+        Span::from(&self.ui.help_title)
+    }
+
+    pub fn help_width(&self) -> u16 {
+        self.ui.help_width
+    }
+
+    pub fn help_section<'a>(&'a self, section: &'a str) -> Span<'a> {
+        Span::styled(section, self.styles.help_section)
+    }
+
+    pub fn help_key(&self, key: KeyCombination, width: usize) -> Span<'_> {
+        Span::styled(format!("{:<width$}", key.to_string()), self.styles.help_key)
+    }
+
+    pub fn stats_completed_title(&self, weekly: bool, max: u64) -> Span<'_> {
+        Span::raw(stats_title(&self.ui.stats_completed_title, weekly, max))
+    }
+
+    pub fn stats_overdue_title(&self, weekly: bool, max: u64) -> Span<'_> {
+        Span::raw(stats_title(&self.ui.stats_overdue_title, weekly, max))
+    }
+
+    pub fn stats_open_title(&self) -> Span<'_> {
+        Span::from(&self.ui.stats_open_title)
+    }
+
+    pub fn stats_summary_title(&self) -> Span<'_> {
+        Span::from(&self.ui.stats_summary_title)
+    }
+
+    pub fn stats_project<'a>(&'a self, project: &'a str) -> Span<'a> {
+        match project {
+            "" => Span::from(&self.ui.stats_no_project),
+            project => self.item_project(project),
+        }
+    }
+
+    pub fn stats_priority(&self, priority: Option<char>) -> Span<'_> {
+        match priority {
+            Some(priority) => self.item_priority_mark(priority),
+            None => Span::from(&self.ui.stats_no_priority),
+        }
+        .patch_style(self.styles.stats_header)
+    }
+
+    pub fn stats_week(&self, start: NaiveDate) -> String {
+        start.format(&self.ui.stats_week_format).to_string()
+    }
+
+    pub fn stats_total_header(&self) -> Span<'_> {
+        Span::styled(&self.ui.stats_total_header, self.styles.stats_header)
+    }
+
+    pub fn stats_open_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.stats_open_label, self.styles.stats_header)
+    }
+
+    pub fn stats_overdue_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.stats_overdue_label, self.styles.stats_header)
+    }
+
+    pub fn stats_completed_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.stats_completed_label, self.styles.stats_header)
+    }
+
+    pub fn stats_average_label(&self) -> Span<'_> {
+        Span::styled(&self.ui.stats_average_label, self.styles.stats_header)
+    }
+
+    pub fn stats_completed_style(&self) -> Style {
+        self.styles.stats_completed
+    }
+
+    pub fn stats_overdue_style(&self) -> Style {
+        self.styles.stats_overdue
+    }
+
+    pub fn reminder_title(&self) -> Span<'_> {
+        Span::from(&self.ui.reminder_title)
+    }
+
+    pub fn reminder_width(&self) -> u16 {
+        self.ui.reminder_width
+    }
+
+    pub fn reminder_kind(&self, kind: DateKind, width: usize) -> Span<'_> {
+        match kind {
+            DateKind::Due => Span::styled(
+                format!("{:<width$}", self.ui.reminder_due),
+                self.styles.item_due_today,
+            ),
+            DateKind::Threshold => Span::styled(
+                format!("{:<width$}", self.ui.reminder_t),
+                self.styles.item_t_recent,
+            ),
+        }
+    }
+
+    pub fn reminder_kind_width(&self) -> usize {
+        Span::from(&self.ui.reminder_due)
+            .width()
+            .max(Span::from(&self.ui.reminder_t).width())
+    }
+
+    pub fn paste_title(&self, valid: usize, invalid: usize) -> Span<'_> {
+        Span::raw(
+            self.ui
+                .paste_title_format
+                .replacen("{valid}", &valid.to_string(), 1)
+                .replacen("{invalid}", &invalid.to_string(), 1),
+        )
+    }
+
+    pub fn paste_width(&self) -> u16 {
+        self.ui.paste_width
+    }
+
+    pub fn paste_invalid<'a>(&'a self, line: &'a str) -> Span<'a> {
+        Span::styled(line, self.styles.paste_invalid)
+    }
+
+    pub fn paste_error<'a>(&'a self, error: &'a str) -> Span<'a> {
+        Span::styled(error, self.styles.paste_error)
+    }
+
+    /// Content of the status bar, with placeholders like {mode} and {visible}
+    pub fn status_format(&self, compact: bool) -> &str {
+        match compact {
+            true => &self.ui.compact_status_format,
+            false => &self.ui.status_format,
+        }
+    }
+
+    pub fn status_mode(&self, mode: &str) -> Span<'_> {
+        Span::styled(mode.to_owned(), self.styles.status_mode)
+    }
+
+    pub fn status_dirty(&self, dirty: bool) -> Span<'_> {
+        match dirty {
+            true => Span::styled(&self.ui.status_dirty, self.styles.status_dirty),
+            false => Span::raw(&self.ui.status_saved),
+        }
+    }
+
+    pub fn status_marked(&self, marked: usize) -> Span<'_> {
+        match marked {
+            0 => Span::raw(""),
+            marked => Span::raw(self.ui.status_marked_format.replacen(
+                "{n}",
+                &marked.to_string(),
+                1,
+            )),
+        }
+    }
+
+    pub fn status_style(&self) -> Style {
+        self.styles.status
+    }
+
+    pub fn status_message<'a>(&'a self, text: &'a str, error: bool) -> Span<'a> {
+        Span::styled(
+            text,
+            if error {
+                self.styles.status_error
+            } else {
+                self.styles.status_message
+            },
+        )
+    }
+
+    pub fn status_message_ticks(&self) -> u32 {
+        self.ui.status_message_ticks
+    }
+
+    pub fn sort_prompt(&self) -> Span<'_> {
+        Span::from(&self.ui.sort_prompt)
+    }
+
+    pub fn input_error_style(&self) -> Style {
+        self.styles.input_error
+    }
+
     pub fn item_selected_style(&self) -> Style {
-        self.ui.styles.item_selected
+        self.styles.item_selected
     }
 }
+
+fn stats_title(format: &str, weekly: bool, max: u64) -> String {
+    format
+        .replacen("{period}", if weekly { "week" } else { "day" }, 1)
+        .replacen("{max}", &max.to_string(), 1)
+}
@@ -0,0 +1,228 @@
+use ratatui::style::{Color, Style, Stylize};
+use serde::{Deserialize, Serialize};
+
+use super::Styles;
+
+/// Bundled theme providing the base styles
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    Solarized,
+    HighContrast,
+    /// Bold and underline only, used if `NO_COLOR` is set
+    Monochrome,
+}
+
+config_struct! {
+    ThemeFile:
+    /// Bundled theme the styles of the file are applied to
+    pub base: Theme,
+    /// Styles replacing the ones of the base theme
+    pub styles: toml::Table,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 5] = [
+        Theme::Dark,
+        Theme::Light,
+        Theme::Solarized,
+        Theme::HighContrast,
+        Theme::Monochrome,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::Solarized => "solarized",
+            Theme::HighContrast => "high_contrast",
+            Theme::Monochrome => "monochrome",
+        }
+    }
+
+    pub fn styles(self) -> Styles {
+        match self {
+            Theme::Dark => Styles::default(),
+            Theme::Light => light(),
+            Theme::Solarized => solarized(),
+            Theme::HighContrast => high_contrast(),
+            Theme::Monochrome => monochrome(),
+        }
+    }
+}
+
+impl Styles {
+    /// Returns the styles with the entries of the table replacing the matching ones
+    pub fn patched(&self, table: &toml::Table) -> anyhow::Result<Styles> {
+        let mut styles = toml::Table::try_from(self)?;
+        styles.extend(table.clone());
+        Ok(styles.try_into()?)
+    }
+}
+
+/// Returns the style table without colors, keeping only the modifiers
+pub fn without_colors(table: &toml::Table) -> toml::Table {
+    let mut table = table.clone();
+    for (_, style) in table.iter_mut() {
+        let Some(style) = style.as_table_mut() else {
+            continue;
+        };
+        for key in ["fg", "bg", "underline_color"] {
+            style.remove(key);
+        }
+    }
+    table
+}
+
+fn light() -> Styles {
+    const MUTED: Color = Color::DarkGray;
+    const ACCENT: Color = Color::Indexed(130);
+
+    Styles {
+        border: Style::new().fg(MUTED),
+        item_project: Style::new().blue().bold(),
+        item_due_soon: Style::new().fg(ACCENT),
+        item_t_past: Style::new().fg(MUTED),
+        item_urgency: Style::new().fg(ACCENT),
+        item_marked: Style::new().bg(Color::Indexed(252)),
+        filter_disabled: Style::new().fg(MUTED),
+        sidebar_count: Style::new().fg(MUTED),
+        calendar_weekday: Style::new().fg(MUTED),
+        calendar_count: Style::new().fg(ACCENT),
+        sort: Style::new().fg(MUTED),
+        help_key: Style::new().fg(ACCENT),
+        paste_invalid: Style::new().fg(MUTED),
+        status: Style::new().fg(MUTED),
+        status_dirty: Style::new().fg(ACCENT),
+        ..Styles::default()
+    }
+}
+
+fn solarized() -> Styles {
+    const BASE03: Color = Color::Rgb(0x00, 0x2b, 0x36);
+    const BASE02: Color = Color::Rgb(0x07, 0x36, 0x42);
+    const BASE00: Color = Color::Rgb(0x65, 0x7b, 0x83);
+    const YELLOW: Color = Color::Rgb(0xb5, 0x89, 0x00);
+    const ORANGE: Color = Color::Rgb(0xcb, 0x4b, 0x16);
+    const RED: Color = Color::Rgb(0xdc, 0x32, 0x2f);
+    const MAGENTA: Color = Color::Rgb(0xd3, 0x36, 0x82);
+    const BLUE: Color = Color::Rgb(0x26, 0x8b, 0xd2);
+    const CYAN: Color = Color::Rgb(0x2a, 0xa1, 0x98);
+    const GREEN: Color = Color::Rgb(0x85, 0x99, 0x00);
+
+    Styles {
+        border: Style::new().fg(BASE00),
+        item_context: Style::new().fg(GREEN).bold(),
+        item_project: Style::new().fg(CYAN).bold(),
+        item_due_soon: Style::new().fg(YELLOW),
+        item_due_today: Style::new().fg(ORANGE),
+        item_due_overdue: Style::new().fg(RED).bold(),
+        item_t: Style::new().fg(BLUE),
+        item_t_recent: Style::new().fg(MAGENTA).bold(),
+        item_t_past: Style::new().fg(BASE00),
+        item_overdue_row: Style::new().fg(RED),
+        item_urgency: Style::new().fg(YELLOW),
+        item_marked: Style::new().bg(BASE02),
+        filter_disabled: Style::new().fg(BASE00),
+        sidebar_count: Style::new().fg(BASE00),
+        calendar_weekday: Style::new().fg(BASE00),
+        calendar_count: Style::new().fg(YELLOW),
+        search_match: Style::new().fg(BASE03).bg(YELLOW),
+        sort: Style::new().fg(BASE00),
+        help_key: Style::new().fg(YELLOW),
+        stats_completed: Style::new().fg(GREEN),
+        stats_overdue: Style::new().fg(RED),
+        paste_invalid: Style::new().fg(BASE00),
+        paste_error: Style::new().fg(RED),
+        status: Style::new().fg(BASE00),
+        status_dirty: Style::new().fg(YELLOW),
+        status_error: Style::new().fg(RED),
+        input_error: Style::new().fg(RED),
+        ..Styles::default()
+    }
+}
+
+fn high_contrast() -> Styles {
+    Styles {
+        border: Style::new().white(),
+        item_word: Style::new().white(),
+        item_context: Style::new().light_green().bold(),
+        item_project: Style::new().light_cyan().bold(),
+        item_due: Style::new().white(),
+        item_due_soon: Style::new().light_yellow().bold(),
+        item_due_today: Style::new().light_red().bold(),
+        item_due_overdue: Style::new().black().on_light_red().bold(),
+        item_t: Style::new().light_blue(),
+        item_t_recent: Style::new().light_magenta().bold(),
+        item_t_past: Style::new().white(),
+        item_overdue_row: Style::new().light_red(),
+        item_urgency: Style::new().light_yellow(),
+        item_selected: Style::new().bold().underlined(),
+        item_marked: Style::new().black().on_white(),
+        filter_disabled: Style::new().white(),
+        sidebar_count: Style::new().white(),
+        calendar_weekday: Style::new().white().bold(),
+        calendar_count: Style::new().light_yellow().bold(),
+        search_match: Style::new().black().on_light_yellow().bold(),
+        sort: Style::new().white(),
+        help_key: Style::new().light_yellow().bold(),
+        stats_completed: Style::new().light_green(),
+        stats_overdue: Style::new().light_red(),
+        paste_invalid: Style::new().white(),
+        paste_error: Style::new().light_red().bold(),
+        status: Style::new().white(),
+        status_dirty: Style::new().light_yellow().bold(),
+        status_error: Style::new().light_red().bold(),
+        input_error: Style::new().light_red().bold(),
+        ..Styles::default()
+    }
+}
+
+fn monochrome() -> Styles {
+    let plain = Style::new();
+    Styles {
+        border: plain,
+        item_word: plain,
+        item_space: plain,
+        item_context: plain.bold(),
+        item_project: plain.bold(),
+        item_due: plain,
+        item_due_soon: plain,
+        item_due_today: plain.underlined(),
+        item_due_overdue: plain.bold().underlined(),
+        item_t: plain,
+        item_t_recent: plain.underlined(),
+        item_t_past: plain,
+        item_overdue_row: plain.bold(),
+        item_urgency: plain,
+        item_selected: plain.bold(),
+        item_marked: plain.underlined(),
+        filter_disabled: plain,
+        sidebar_count: plain,
+        calendar_weekday: plain,
+        calendar_today: plain.underlined(),
+        calendar_selected: plain.bold().underlined(),
+        calendar_count: plain,
+        board_focused_title: plain.bold(),
+        search_match: plain.bold().underlined(),
+        sort: plain,
+        group_header: plain.bold(),
+        details_label: plain.bold(),
+        help_section: plain.bold().underlined(),
+        help_key: plain.bold(),
+        stats_completed: plain,
+        stats_overdue: plain,
+        stats_header: plain.bold(),
+        paste_invalid: plain,
+        paste_error: plain.bold(),
+        status: plain,
+        status_mode: plain.bold(),
+        status_dirty: plain.bold(),
+        status_message: plain,
+        status_error: plain.bold(),
+        input_error: plain.underlined(),
+    }
+}
@@ -1,7 +1,21 @@
-use crate::app::{App, FocusState, SortedFilteredTodoList};
+use std::{
+    collections::BTreeSet,
+    time::{Duration, Instant},
+};
+
+use crate::app::{parse_date_input, App, FocusState, PastedLine, SortedFilteredTodoList};
+use crate::stats::Statistics;
+use crate::view::{BatchPrompt, DateKind, SortKey};
+use chrono::TimeDelta;
 use crokey::{key, KeyCombination};
-use ratatui::crossterm::event::KeyEvent;
-use tui_input::InputRequest;
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
+    layout::Position,
+};
+use tui_input::{Input, InputRequest};
+
+/// Maximum time between two clicks on the same row to count as double click
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_event(
@@ -15,9 +29,20 @@ pub fn handle_key_event(
                 app.quit();
                 return Ok(());
             }
+            if key != app.config.keys.quit {
+                app.quit_pending = false;
+            }
 
             let old_state = app.take_state();
-            let new_app_state = handle_state(input, key, app, old_state);
+            if key == app.config.keys.help && !matches!(old_state, FocusState::HelpFocus { .. }) {
+                app.state = FocusState::HelpFocus {
+                    previous_state: Box::new(old_state),
+                    scroll: 0,
+                };
+                return Ok(());
+            }
+
+            let new_app_state = handle_state(input, key, app, old_state)?;
             app.state = new_app_state;
 
             Ok(())
@@ -26,37 +51,150 @@ pub fn handle_key_event(
     }
 }
 
+/// Handles pasted text and updates the state of [`App`].
+pub fn handle_paste_event(text: String, app: &mut App) -> anyhow::Result<()> {
+    let state = app.take_state();
+    app.state = match state {
+        FocusState::ListFocus => {
+            let lines = PastedLine::parse_all(&text, app.todo_list.today());
+            match lines.is_empty() {
+                true => FocusState::ListFocus,
+                false => FocusState::PasteFocus { lines, scroll: 0 },
+            }
+        }
+        FocusState::FilterFocus { .. }
+        | FocusState::SearchFocus { .. }
+        | FocusState::SortFocus { .. }
+        | FocusState::BatchFocus { .. }
+        | FocusState::RescheduleFocus { .. } => {
+            // Type the text into the input with a key that is never bound
+            let key = KeyCombination::from(KeyCode::Null);
+            text.chars()
+                .map(|c| if c.is_whitespace() { ' ' } else { c })
+                .filter(|c| !c.is_control())
+                .try_fold(state, |state, c| {
+                    handle_state(Some(InputRequest::InsertChar(c)), key, app, state)
+                })?
+        }
+        state => state,
+    };
+
+    Ok(())
+}
+
+/// Handles the mouse events and updates the state of [`App`].
+pub fn handle_mouse_event(mouse_event: MouseEvent, app: &mut App) -> anyhow::Result<()> {
+    if let FocusState::HelpFocus { scroll, .. }
+    | FocusState::PasteFocus { scroll, .. }
+    | FocusState::ReminderFocus { scroll, .. } = &mut app.state
+    {
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => *scroll = scroll.saturating_add(1),
+            MouseEventKind::ScrollUp => *scroll = scroll.saturating_sub(1),
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    if !matches!(
+        app.state,
+        FocusState::ListFocus | FocusState::FilterFocus { .. }
+    ) {
+        return Ok(());
+    }
+
+    let previous_selection = match app.state {
+        FocusState::FilterFocus { previous_selection } => previous_selection,
+        _ => None,
+    };
+    let update_index = |todo_list: &mut SortedFilteredTodoList| {
+        if let Some(index) = previous_selection {
+            todo_list.select_index(index);
+        }
+    };
+    let position = Position::new(mouse_event.column, mouse_event.row);
+    let areas = &app.click_areas;
+    match mouse_event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if areas.completion_filter.contains(position) {
+                app.todo_list.mutate_filter(|f| {
+                    f.cycle_completion();
+                });
+                update_index(&mut app.todo_list);
+            } else if areas.priority_filter.contains(position) {
+                let priorities = app.todo_list.priorities();
+                app.todo_list.mutate_filter(|f| {
+                    f.cycle_priority(&priorities);
+                });
+                update_index(&mut app.todo_list);
+            } else if areas.t_filter.contains(position) {
+                app.todo_list.mutate_filter(|f| {
+                    f.t = !f.t;
+                });
+                update_index(&mut app.todo_list);
+            } else if let Some(row) = areas
+                .list_rows
+                .iter()
+                .find(|(_, area)| area.contains(position))
+                .map(|(row, _)| *row)
+            {
+                if !matches!(app.state, FocusState::ListFocus) {
+                    return Ok(());
+                }
+
+                if app.todo_list.select_row(row) {
+                    let double_click = app.last_click.is_some_and(|(time, last_row)| {
+                        last_row == row && time.elapsed() < DOUBLE_CLICK_TIME
+                    });
+                    if double_click {
+                        app.last_click = None;
+                        if let Some(index) = app.todo_list.selected_index() {
+                            app.todo_list.toggle_completion(index);
+                        }
+                    } else {
+                        app.last_click = Some((Instant::now(), row));
+                    }
+                } else {
+                    app.todo_list.toggle_group_at(row);
+                }
+            }
+        }
+        MouseEventKind::ScrollDown if areas.list.contains(position) => {
+            app.todo_list.move_selection(1, false);
+        }
+        MouseEventKind::ScrollUp if areas.list.contains(position) => {
+            app.todo_list.move_selection(-1, false);
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
 fn handle_state(
     input: Option<InputRequest>,
     key: KeyCombination,
     app: &mut App,
     state: FocusState,
-) -> FocusState {
+) -> anyhow::Result<FocusState> {
     match state {
-        FocusState::FilterFocus {
-            previous_selection_index,
-            previous_selection_item,
-        } => {
+        FocusState::FilterFocus { previous_selection } => {
             let update_index = |todo_list: &mut SortedFilteredTodoList| {
-                let index = todo_list
-                    .items()
-                    .enumerate()
-                    .find(|(_, item)| *item == &previous_selection_item)
-                    .map(|(index, _)| index)
-                    .unwrap_or_default();
-                todo_list.table_state_mut().select(Some(index));
+                if let Some(index) = previous_selection {
+                    todo_list.select_index(index);
+                }
             };
             if [app.config.keys.cancel, app.config.keys.confirm].contains(&key) {
-                return FocusState::ListFocus;
+                return Ok(FocusState::ListFocus);
             } else if key == app.config.keys.priority {
-                // TODO
+                let priorities = app.todo_list.priorities();
+                app.todo_list.mutate_filter(|f| {
+                    f.cycle_priority(&priorities);
+                });
+                update_index(&mut app.todo_list);
             } else if key == app.config.keys.completion {
                 app.todo_list.mutate_filter(|f| {
-                    f.completion = match f.completion {
-                        None => Some(true),
-                        Some(true) => Some(false),
-                        Some(false) => None,
-                    };
+                    f.cycle_completion();
                 });
                 update_index(&mut app.todo_list);
             } else if key == app.config.keys.t {
@@ -64,6 +202,27 @@ fn handle_state(
                     f.t = !f.t;
                 });
                 update_index(&mut app.todo_list);
+            } else if key == app.config.keys.hidden {
+                app.todo_list.mutate_filter(|f| {
+                    f.hidden = !f.hidden;
+                });
+                update_index(&mut app.todo_list);
+            } else if key == app.config.keys.due {
+                let within_days = app.config.filter.due_within_days;
+                app.todo_list.mutate_filter(|f| {
+                    f.due = f.due.next(within_days);
+                });
+                update_index(&mut app.todo_list);
+            } else if key == app.config.keys.word_logic {
+                app.todo_list.mutate_filter(|f| {
+                    f.word_logic = f.word_logic.toggled();
+                });
+                update_index(&mut app.todo_list);
+            } else if key == app.config.keys.word_match {
+                app.todo_list.mutate_filter(|f| {
+                    f.word_match = f.word_match.next();
+                });
+                update_index(&mut app.todo_list);
             } else if let Some(input) = input {
                 app.todo_list.mutate_filter(|f| {
                     f.input_field.handle(input);
@@ -71,45 +230,585 @@ fn handle_state(
                 update_index(&mut app.todo_list);
             }
 
-            FocusState::FilterFocus {
-                previous_selection_index,
-                previous_selection_item,
-            }
+            Ok(FocusState::FilterFocus { previous_selection })
         }
         FocusState::ListFocus => {
+            let count = app.count_prefix.take();
+            let top_pending = std::mem::take(&mut app.top_pending);
+            if let Some(digit) = key.as_letter().and_then(|c| c.to_digit(10)) {
+                if digit != 0 || count.is_some() {
+                    let count = count.unwrap_or_default().saturating_mul(10);
+                    app.count_prefix = Some(count.saturating_add(digit as usize));
+                    return Ok(FocusState::ListFocus);
+                }
+            }
+
             if key == app.config.keys.quit {
-                app.quit();
+                app.request_quit();
             } else if key == app.config.keys.focus_filter {
-                let previous_selection_index = app
-                    .todo_list
-                    .table_state_mut()
-                    .selected()
-                    .expect("There should be one item selected");
-                let previous_selection_item = app
+                return Ok(FocusState::FilterFocus {
+                    previous_selection: app.todo_list.selected_index(),
+                });
+            } else if key == app.config.keys.sidebar {
+                app.sidebar.visible = true;
+                return Ok(FocusState::SidebarFocus);
+            } else if key == app.config.keys.calendar {
+                app.calendar.visible = true;
+                let day = app.calendar.day;
+                app.todo_list.mutate_filter(|f| {
+                    f.day = Some(day);
+                });
+                return Ok(FocusState::CalendarFocus);
+            } else if key == app.config.keys.agenda {
+                return Ok(FocusState::AgendaFocus);
+            } else if key == app.config.keys.board {
+                return Ok(FocusState::BoardFocus);
+            } else if key == app.config.keys.search {
+                let previous_selection = app.todo_list.table_state_mut().selected();
+                app.todo_list.mutate_search(|s| s.reset());
+                return Ok(FocusState::SearchFocus { previous_selection });
+            } else if key == app.config.keys.cycle_sort {
+                let presets = &app.config.sort.presets;
+                if !presets.is_empty() {
+                    let next = presets
+                        .iter()
+                        .position(|preset| preset == app.todo_list.sort())
+                        .map_or(0, |i| (i + 1) % presets.len());
+                    app.todo_list.set_sort(presets[next].clone());
+                }
+            } else if key == app.config.keys.toggle_completion {
+                let targets = app.todo_list.targets();
+                let today = app.todo_list.today();
+                let list = app.todo_list.list();
+                let complete = targets
+                    .iter()
+                    .any(|index| list[*index].completion_date.is_none());
+                app.todo_list.edit_items(&targets, |item| {
+                    item.completion_date = complete.then_some(today);
+                });
+                if targets.len() > 1 {
+                    let action = if complete { "Completed" } else { "Reopened" };
+                    app.show_message(format!("{action} {} items", targets.len()));
+                }
+            } else if key == app.config.keys.cancel && !app.todo_list.marks().is_empty() {
+                app.todo_list.set_marks(BTreeSet::new());
+            } else if key == app.config.keys.mark {
+                if let Some(index) = app.todo_list.selected_index() {
+                    app.todo_list.toggle_mark(index);
+                    app.todo_list.move_selection(1, false);
+                }
+            } else if key == app.config.keys.visual {
+                if let Some(anchor) = app.todo_list.selected_index() {
+                    let base_marks = app.todo_list.marks().clone();
+                    let mut marks = base_marks.clone();
+                    marks.insert(anchor);
+                    app.todo_list.set_marks(marks);
+                    return Ok(FocusState::VisualFocus { anchor, base_marks });
+                }
+            } else if let Some(prompt) = [
+                (app.config.keys.set_priority, BatchPrompt::SetPriority),
+                (app.config.keys.add_category, BatchPrompt::AddCategories),
+                (
+                    app.config.keys.remove_category,
+                    BatchPrompt::RemoveCategories,
+                ),
+            ]
+            .into_iter()
+            .find_map(|(prompt_key, prompt)| (prompt_key == key).then_some(prompt))
+            {
+                let indices = app.todo_list.targets();
+                if !indices.is_empty() {
+                    return Ok(FocusState::BatchFocus {
+                        input_field: Input::default(),
+                        prompt,
+                        indices,
+                    });
+                }
+            } else if key == app.config.keys.postpone {
+                let today = app.todo_list.today();
+                let targets = app.todo_list.targets();
+                app.todo_list.edit_items(&targets, |item| {
+                    item.due =
+                        Some(item.due.map_or(today, |due| due.max(today)) + TimeDelta::days(1));
+                });
+            } else if key == app.config.keys.archive {
+                let targets = app.todo_list.targets();
+                if app.archive_path.is_none() {
+                    app.show_error("No archive file given");
+                } else if targets.is_empty() {
+                    app.show_error("Nothing selected");
+                } else {
+                    app.todo_list.remove_items(&targets, true);
+                    app.show_message(format!("Archived {} items", targets.len()));
+                }
+            } else if key == app.config.keys.delete {
+                let targets = app.todo_list.targets();
+                if targets.is_empty() {
+                    app.show_error("Nothing selected");
+                } else {
+                    app.todo_list.remove_items(&targets, false);
+                    app.show_message(format!("Deleted {} items", targets.len()));
+                }
+            } else if key == app.config.keys.undo {
+                if app.todo_list.undo() {
+                    app.show_message("Undid last change");
+                } else {
+                    app.show_message("Nothing to undo");
+                }
+            } else if key == app.config.keys.details {
+                app.details_visible = !app.details_visible;
+            } else if key == app.config.keys.save {
+                match app.save() {
+                    Ok(()) => app.show_message(format!(
+                        "Saved {} items to {}",
+                        app.todo_list.list().len(),
+                        app.todo_path.display()
+                    )),
+                    Err(e) => app.show_error(format!("Failed to save: {e}")),
+                }
+            } else if key == app.config.keys.stats {
+                let archive = app.read_archive().unwrap_or_else(|e| {
+                    app.show_error(format!("Failed to read archive: {e}"));
+                    vec![]
+                });
+                let items = app
                     .todo_list
-                    .items()
-                    .nth(previous_selection_index)
-                    .unwrap()
-                    .clone();
-                return FocusState::FilterFocus {
-                    previous_selection_index,
-                    previous_selection_item,
-                };
-            } else if app.todo_list.items().len() > 0 {
+                    .list()
+                    .iter()
+                    .chain(app.todo_list.archived())
+                    .chain(&archive);
+                let statistics = Statistics::new(
+                    items,
+                    app.todo_list.today(),
+                    app.config.stats.days,
+                    app.config.stats.weeks,
+                );
+                return Ok(FocusState::StatsFocus {
+                    statistics,
+                    weekly: false,
+                });
+            } else if key == app.config.keys.cycle_theme {
+                if let Some(name) = app.config.cycle_theme() {
+                    let message = format!("Theme: {name}");
+                    app.show_message(message);
+                }
+            } else if key == app.config.keys.cycle_group {
+                let grouping = app.todo_list.grouping().next();
+                app.todo_list.set_grouping(grouping);
+            } else if key == app.config.keys.edit_sort {
+                let previous_sort = app.todo_list.sort().to_vec();
+                return Ok(FocusState::SortFocus {
+                    input_field: Input::new(SortKey::format_order(&previous_sort)),
+                    previous_sort,
+                });
+            } else if !app.todo_list.rows().is_empty() {
+                let steps = count.unwrap_or(1) as isize;
+                let wrap = app.config.wrap_selection() && count.is_none();
+                let page = app.list_page_size() as isize;
                 if key == app.config.keys.up {
-                    let mut table_state = app.todo_list.table_state_mut();
-                    let len = app.todo_list.items().len();
-                    let selected = table_state.selected().map(|i| (i + len - 1) % len);
-                    table_state.select(selected);
+                    app.todo_list.move_selection(-steps, wrap);
                 } else if key == app.config.keys.down {
-                    let mut table_state = app.todo_list.table_state_mut();
-                    let len = app.todo_list.items().len();
-                    let selected = table_state.selected().map(|i| (i + 1) % len);
-                    table_state.select(selected);
+                    app.todo_list.move_selection(steps, wrap);
+                } else if key == app.config.keys.page_up {
+                    app.todo_list.move_selection(-steps * page, false);
+                } else if key == app.config.keys.page_down {
+                    app.todo_list.move_selection(steps * page, false);
+                } else if key == app.config.keys.half_page_up {
+                    app.todo_list
+                        .move_selection(-steps * (page / 2).max(1), false);
+                } else if key == app.config.keys.half_page_down {
+                    app.todo_list
+                        .move_selection(steps * (page / 2).max(1), false);
+                } else if key == app.config.keys.top {
+                    if top_pending {
+                        app.todo_list
+                            .select_nth_item(count.unwrap_or(1).saturating_sub(1));
+                    } else {
+                        app.top_pending = true;
+                        app.count_prefix = count;
+                    }
+                } else if key == app.config.keys.bottom {
+                    app.todo_list
+                        .select_nth_item(count.map_or(usize::MAX, |n| n.saturating_sub(1)));
+                } else if [app.config.keys.move_up, app.config.keys.move_down].contains(&key) {
+                    if app.todo_list.is_file_sorted() {
+                        let offset = if key == app.config.keys.move_up {
+                            -1
+                        } else {
+                            1
+                        };
+                        app.todo_list.move_selected(offset);
+                    } else {
+                        app.show_error("Reordering requires file sort");
+                    }
+                } else if key == app.config.keys.fold {
+                    app.todo_list.toggle_selected_group();
+                } else if key == app.config.keys.unfold_all {
+                    app.todo_list.expand_all_groups();
+                } else if key == app.config.keys.search_next {
+                    let selected = app.todo_list.table_state_mut().selected();
+                    app.todo_list
+                        .select_search_match(selected.map_or(0, |i| i + 1), true);
+                } else if key == app.config.keys.search_previous {
+                    let len = app.todo_list.rows().len();
+                    let selected = app.todo_list.table_state_mut().selected();
+                    app.todo_list
+                        .select_search_match(selected.map_or(0, |i| i + len - 1), false);
                 }
             }
 
-            FocusState::ListFocus
+            Ok(FocusState::ListFocus)
+        }
+        FocusState::SidebarFocus => {
+            if key == app.config.keys.sidebar {
+                app.sidebar.visible = false;
+                return Ok(FocusState::ListFocus);
+            } else if [app.config.keys.cancel, app.config.keys.confirm].contains(&key) {
+                return Ok(FocusState::ListFocus);
+            }
+
+            let counts = app.todo_list.category_counts();
+            if !counts.is_empty() {
+                let len = counts.len();
+                let mut table_state = app.sidebar.table_state_mut();
+                let selected = table_state.selected().unwrap_or_default().min(len - 1);
+                if key == app.config.keys.up {
+                    table_state.select(Some((selected + len - 1) % len));
+                } else if key == app.config.keys.down {
+                    table_state.select(Some((selected + 1) % len));
+                } else if key == app.config.keys.select {
+                    let category = counts.into_keys().nth(selected).unwrap();
+                    app.todo_list.mutate_filter(|f| {
+                        category.toggle_in(&mut f.categories);
+                    });
+                }
+            }
+
+            Ok(FocusState::SidebarFocus)
+        }
+        FocusState::CalendarFocus => {
+            if key == app.config.keys.calendar {
+                app.calendar.visible = false;
+                app.todo_list.mutate_filter(|f| {
+                    f.day = None;
+                });
+                return Ok(FocusState::ListFocus);
+            } else if [app.config.keys.cancel, app.config.keys.confirm].contains(&key) {
+                return Ok(FocusState::ListFocus);
+            }
+
+            if key == app.config.keys.left {
+                app.calendar.move_days(-1);
+            } else if key == app.config.keys.right {
+                app.calendar.move_days(1);
+            } else if key == app.config.keys.up {
+                app.calendar.move_days(-7);
+            } else if key == app.config.keys.down {
+                app.calendar.move_days(7);
+            } else if key == app.config.keys.previous_month {
+                app.calendar.move_months(-1);
+            } else if key == app.config.keys.next_month {
+                app.calendar.move_months(1);
+            }
+            let day = app.calendar.day;
+            if app.todo_list.filter().day != Some(day) {
+                app.todo_list.mutate_filter(|f| {
+                    f.day = Some(day);
+                });
+            }
+
+            Ok(FocusState::CalendarFocus)
+        }
+        FocusState::VisualFocus { anchor, base_marks } => {
+            if key == app.config.keys.cancel {
+                app.todo_list.set_marks(base_marks);
+                return Ok(FocusState::ListFocus);
+            } else if [app.config.keys.visual, app.config.keys.confirm].contains(&key) {
+                return Ok(FocusState::ListFocus);
+            }
+
+            // Everything else behaves as in the list, extending the marks on movement
+            let revision = app.todo_list.revision();
+            let state = handle_state(input, key, app, FocusState::ListFocus)?;
+            if !matches!(state, FocusState::ListFocus) || app.todo_list.revision() != revision {
+                return Ok(state);
+            }
+            let (Some(anchor_row), Some(selected_row)) = (
+                app.todo_list.item_row(anchor),
+                app.todo_list.table_state_mut().selected(),
+            ) else {
+                return Ok(FocusState::ListFocus);
+            };
+            let mut marks = base_marks.clone();
+            marks.extend(app.todo_list.items_between_rows(anchor_row, selected_row));
+            app.todo_list.set_marks(marks);
+
+            Ok(FocusState::VisualFocus { anchor, base_marks })
+        }
+        FocusState::BatchFocus {
+            mut input_field,
+            prompt,
+            indices,
+        } => {
+            if key == app.config.keys.cancel {
+                return Ok(FocusState::ListFocus);
+            } else if key == app.config.keys.confirm {
+                match prompt.parse(input_field.value()) {
+                    Ok(edit) => {
+                        app.todo_list.edit_items(&indices, |item| edit.apply(item));
+                        if indices.len() > 1 {
+                            app.show_message(format!("Changed {} items", indices.len()));
+                        }
+                        return Ok(FocusState::ListFocus);
+                    }
+                    Err(error) => app.show_error(error),
+                }
+            } else if let Some(input) = input {
+                input_field.handle(input);
+            }
+
+            Ok(FocusState::BatchFocus {
+                input_field,
+                prompt,
+                indices,
+            })
+        }
+        FocusState::BoardFocus => {
+            if [app.config.keys.board, app.config.keys.cancel].contains(&key) {
+                return Ok(FocusState::ListFocus);
+            }
+
+            let by = app.config.board.column_by;
+            let columns = &app.config.board.columns;
+            let cards = app.todo_list.board_cards(by, columns);
+            if cards.is_empty() {
+                return Ok(FocusState::BoardFocus);
+            }
+            app.board.column = app.board.column.min(cards.len() - 1);
+            app.board.clamp_selections(&cards);
+
+            let column = app.board.column;
+            if key == app.config.keys.left {
+                app.board.column = column.saturating_sub(1);
+            } else if key == app.config.keys.right {
+                app.board.column = (column + 1).min(cards.len() - 1);
+            } else if [app.config.keys.up, app.config.keys.down].contains(&key) {
+                let mut state = app.board.column_state_mut(column);
+                if let Some(selected) = state.selected() {
+                    state.select(Some(if key == app.config.keys.up {
+                        selected.saturating_sub(1)
+                    } else {
+                        (selected + 1).min(cards[column].len() - 1)
+                    }));
+                }
+            } else if let Some(index) = app.board.selected(&cards) {
+                if [app.config.keys.move_left, app.config.keys.move_right].contains(&key) {
+                    let target = if key == app.config.keys.move_left {
+                        column.saturating_sub(1)
+                    } else {
+                        (column + 1).min(cards.len() - 1)
+                    };
+                    if target != column {
+                        app.todo_list
+                            .move_to_column(index, by, &columns[column], &columns[target]);
+                        let target = by.column_of(&app.todo_list.list()[index], columns);
+                        let cards = app.todo_list.board_cards(by, columns);
+                        app.board.column = target;
+                        app.board.select(&cards, target, index);
+                    }
+                } else if key == app.config.keys.toggle_completion {
+                    app.todo_list.toggle_completion(index);
+                }
+            }
+
+            Ok(FocusState::BoardFocus)
+        }
+        FocusState::AgendaFocus => {
+            if [app.config.keys.agenda, app.config.keys.cancel].contains(&key) {
+                return Ok(FocusState::ListFocus);
+            }
+
+            let rows = app
+                .todo_list
+                .agenda_rows(app.config.agenda.days, app.config.agenda.undated);
+            app.agenda.move_selection(&rows, 0);
+            let Some((index, kind)) = app.agenda.selected(&rows) else {
+                return Ok(FocusState::AgendaFocus);
+            };
+            if key == app.config.keys.up {
+                app.agenda.move_selection(&rows, -1);
+            } else if key == app.config.keys.down {
+                app.agenda.move_selection(&rows, 1);
+            } else if key == app.config.keys.toggle_completion {
+                app.todo_list.toggle_completion(index);
+            } else if key == app.config.keys.postpone {
+                let today = app.todo_list.today();
+                let item = &app.todo_list.list()[index];
+                let date = match kind {
+                    DateKind::Due => item.due,
+                    DateKind::Threshold => item.t,
+                };
+                let date = date.map_or(today, |date| date.max(today)) + TimeDelta::days(1);
+                app.todo_list.set_date(index, kind, date);
+            } else if key == app.config.keys.reschedule {
+                return Ok(FocusState::RescheduleFocus {
+                    input_field: Input::default(),
+                    index,
+                    kind,
+                });
+            }
+
+            Ok(FocusState::AgendaFocus)
+        }
+        FocusState::StatsFocus {
+            statistics,
+            mut weekly,
+        } => {
+            if [app.config.keys.stats, app.config.keys.cancel].contains(&key) {
+                return Ok(FocusState::ListFocus);
+            } else if key == app.config.keys.stats_period {
+                weekly = !weekly;
+            }
+
+            Ok(FocusState::StatsFocus { statistics, weekly })
+        }
+        FocusState::RescheduleFocus {
+            mut input_field,
+            index,
+            kind,
+        } => {
+            if key == app.config.keys.cancel {
+                return Ok(FocusState::AgendaFocus);
+            } else if key == app.config.keys.confirm {
+                match parse_date_input(input_field.value(), app.todo_list.today()) {
+                    Ok(date) => {
+                        app.todo_list.set_date(index, kind, date);
+                        return Ok(FocusState::AgendaFocus);
+                    }
+                    Err(error) => app.show_error(error),
+                }
+            } else if let Some(input) = input {
+                input_field.handle(input);
+            }
+
+            Ok(FocusState::RescheduleFocus {
+                input_field,
+                index,
+                kind,
+            })
+        }
+        FocusState::SearchFocus { previous_selection } => {
+            if key == app.config.keys.cancel {
+                app.todo_list.mutate_search(|s| s.reset());
+                app.todo_list.table_state_mut().select(previous_selection);
+                return Ok(FocusState::ListFocus);
+            } else if key == app.config.keys.confirm {
+                return Ok(FocusState::ListFocus);
+            } else if let Some(input) = input {
+                app.todo_list.mutate_search(|s| {
+                    s.handle(input);
+                });
+                if !app
+                    .todo_list
+                    .select_search_match(previous_selection.unwrap_or_default(), true)
+                {
+                    app.todo_list.table_state_mut().select(previous_selection);
+                }
+            }
+
+            Ok(FocusState::SearchFocus { previous_selection })
+        }
+        FocusState::SortFocus {
+            mut input_field,
+            previous_sort,
+        } => {
+            if key == app.config.keys.cancel {
+                app.todo_list.set_sort(previous_sort);
+                return Ok(FocusState::ListFocus);
+            } else if key == app.config.keys.confirm {
+                if SortKey::parse_order(input_field.value()).is_ok() {
+                    return Ok(FocusState::ListFocus);
+                }
+            } else if let Some(input) = input {
+                input_field.handle(input);
+                if let Ok(sort) = SortKey::parse_order(input_field.value()) {
+                    app.todo_list.set_sort(sort);
+                }
+            }
+
+            Ok(FocusState::SortFocus {
+                input_field,
+                previous_sort,
+            })
+        }
+        FocusState::PasteFocus { lines, mut scroll } => {
+            if key == app.config.keys.cancel {
+                return Ok(FocusState::ListFocus);
+            } else if key == app.config.keys.confirm {
+                let line_count = lines.len();
+                let items: Vec<_> = lines
+                    .into_iter()
+                    .filter_map(|line| line.item.ok())
+                    .collect();
+                match line_count - items.len() {
+                    0 => app.show_message(format!("Added {} items", items.len())),
+                    invalid => app.show_message(format!(
+                        "Added {} items, skipped {invalid} invalid lines",
+                        items.len()
+                    )),
+                }
+                app.todo_list.add_items(items);
+                return Ok(FocusState::ListFocus);
+            } else if key == app.config.keys.up {
+                scroll = scroll.saturating_sub(1);
+            } else if key == app.config.keys.down {
+                scroll = scroll.saturating_add(1);
+            }
+
+            Ok(FocusState::PasteFocus { lines, scroll })
+        }
+        FocusState::ReminderFocus {
+            reminders,
+            previous_state,
+            mut scroll,
+        } => {
+            if [app.config.keys.cancel, app.config.keys.confirm].contains(&key) {
+                return Ok(*previous_state);
+            } else if key == app.config.keys.up {
+                scroll = scroll.saturating_sub(1);
+            } else if key == app.config.keys.down {
+                scroll = scroll.saturating_add(1);
+            }
+
+            Ok(FocusState::ReminderFocus {
+                reminders,
+                previous_state,
+                scroll,
+            })
+        }
+        FocusState::HelpFocus {
+            previous_state,
+            mut scroll,
+        } => {
+            if [
+                app.config.keys.help,
+                app.config.keys.cancel,
+                app.config.keys.confirm,
+                app.config.keys.quit,
+            ]
+            .contains(&key)
+            {
+                return Ok(*previous_state);
+            } else if key == app.config.keys.up {
+                scroll = scroll.saturating_sub(1);
+            } else if key == app.config.keys.down {
+                scroll = scroll.saturating_add(1);
+            }
+
+            Ok(FocusState::HelpFocus {
+                previous_state,
+                scroll,
+            })
         }
         FocusState::Invalid => unreachable!(),
     }
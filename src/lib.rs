@@ -18,3 +18,12 @@ pub mod config;
 
 /// Todo format parsing and representation
 pub mod todo;
+
+/// Urgency computation of todo items
+pub mod urgency;
+
+/// Statistics over completed and open items
+pub mod stats;
+
+/// Sorting, grouping and other options of the views shared by the app, config and ui
+pub mod view;
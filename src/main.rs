@@ -3,11 +3,11 @@ use directories::ProjectDirs;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use totui::app::App;
 use totui::config::Config;
 use totui::event::{Event, EventHandler};
-use totui::handler::handle_key_event;
+use totui::handler::{handle_key_event, handle_mouse_event, handle_paste_event};
 use totui::tui::Tui;
 
 #[derive(clap::Parser, Debug)]
@@ -23,7 +23,7 @@ struct Args {
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    let config: Config = match &args.config_file {
+    let mut config: Config = match &args.config_file {
         Some(file) => toml::from_str(&std::fs::read_to_string(file)?)?,
         None => {
             if let Some(dirs) = ProjectDirs::from("", "", env!("CARGO_PKG_NAME")) {
@@ -40,13 +40,21 @@ fn main() -> anyhow::Result<()> {
         }
     };
 
+    let config_dir = match &args.config_file {
+        Some(file) => file.parent().map(Path::to_path_buf),
+        None => ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))
+            .map(|dirs| dirs.config_dir().to_path_buf()),
+    };
+    config.validate_date_formats()?;
+    config.load_themes(&config_dir.unwrap_or_default())?;
+
     let todo_file_content = std::fs::read_to_string(&args.todo_file)?;
     let todo_list = todo_file_content
         .parse()
         .or_else(|e| anyhow::bail!("Failed to parse TODO file!\n{e}"))?;
 
     // Create an application.
-    let mut app = App::new(todo_list, args.archive_file, config);
+    let mut app = App::new(todo_list, args.todo_file, args.archive_file, config);
 
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stderr());
@@ -54,7 +62,9 @@ fn main() -> anyhow::Result<()> {
     let events = EventHandler::new(250);
     let mut tui = Tui::new(terminal, events);
 
-    tui.init()?;
+    tui.init(app.config.mouse())?;
+    let (width, height) = ratatui::crossterm::terminal::size()?;
+    app.resize(width, height);
 
     // Start the main loop.
     while app.running {
@@ -62,10 +72,16 @@ fn main() -> anyhow::Result<()> {
         tui.draw(&mut app)?;
         // Handle events.
         match tui.events.next()? {
-            Event::Tick => app.tick(),
+            Event::Tick => {
+                if app.tick() {
+                    // Failing to ring the bell is no reason to quit
+                    let _ = tui.bell();
+                }
+            }
             Event::Key(key_event, input) => handle_key_event(key_event, input, &mut app)?,
-            Event::Mouse(_) => {}
-            Event::Resize(_, _) => {}
+            Event::Mouse(mouse_event) => handle_mouse_event(mouse_event, &mut app)?,
+            Event::Resize(width, height) => app.resize(width, height),
+            Event::Paste(text) => handle_paste_event(text, &mut app)?,
         }
     }
 
@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Datelike, NaiveDate, TimeDelta};
+
+use crate::todo::TodoItem;
+
+/// Statistics over the items of the todo and archive file
+#[derive(Debug, Clone, Default)]
+pub struct Statistics {
+    /// Items completed per day, oldest first and ending today
+    pub completed_days: Vec<u64>,
+    /// Items completed per week, oldest first and ending with the current week
+    pub completed_weeks: Vec<u64>,
+    /// Items overdue at each day, oldest first and ending today
+    pub overdue_days: Vec<u64>,
+    /// Items overdue at the end of each week, oldest first and ending today
+    pub overdue_weeks: Vec<u64>,
+    /// First days of the weeks
+    pub week_starts: Vec<NaiveDate>,
+    /// Open items per project, an empty project collecting items without project
+    pub open: BTreeMap<String, BTreeMap<Option<char>, usize>>,
+    /// Priorities of the open items, no priority last
+    pub priorities: Vec<Option<char>>,
+    /// Amount of open items
+    pub open_total: usize,
+    /// Amount of completed items
+    pub completed_total: usize,
+    /// Amount of items overdue today
+    pub overdue_total: usize,
+    /// Average amount of days from creation to completion
+    pub average_completion_days: Option<f64>,
+}
+
+impl Statistics {
+    /// Computes the statistics of the items over the given amount of days and weeks up to today
+    pub fn new<'a>(
+        items: impl IntoIterator<Item = &'a TodoItem>,
+        today: NaiveDate,
+        days: usize,
+        weeks: usize,
+    ) -> Self {
+        let items: Vec<_> = items.into_iter().collect();
+        let mut statistics = Statistics::default();
+
+        let first_day = today - TimeDelta::days(days.saturating_sub(1) as i64);
+        let current_week = today - TimeDelta::days(today.weekday().num_days_from_monday() as i64);
+        let first_week = current_week - TimeDelta::weeks(weeks.saturating_sub(1) as i64);
+        statistics.week_starts = first_week.iter_weeks().take(weeks).collect();
+
+        let completion_dates: Vec<_> = items
+            .iter()
+            .filter_map(|item| item.completion_date)
+            .collect();
+        let completed_between = |from: NaiveDate, to: NaiveDate| {
+            completion_dates
+                .iter()
+                .filter(|date| (from..to).contains(date))
+                .count() as u64
+        };
+        let overdue_at = |day: NaiveDate| {
+            items
+                .iter()
+                .filter(|item| {
+                    item.creation_date <= day
+                        && item.due.is_some_and(|due| due < day)
+                        && item.completion_date.is_none_or(|date| date > day)
+                })
+                .count() as u64
+        };
+
+        for day in first_day.iter_days().take(days) {
+            statistics
+                .completed_days
+                .push(completed_between(day, day + TimeDelta::days(1)));
+            statistics.overdue_days.push(overdue_at(day));
+        }
+        for start in &statistics.week_starts {
+            let end = *start + TimeDelta::weeks(1);
+            statistics
+                .completed_weeks
+                .push(completed_between(*start, end));
+            statistics
+                .overdue_weeks
+                .push(overdue_at((end - TimeDelta::days(1)).min(today)));
+        }
+
+        let mut priorities = BTreeSet::new();
+        for item in items.iter().filter(|item| item.completion_date.is_none()) {
+            statistics.open_total += 1;
+            priorities.insert(item.priority);
+            let mut projects: Vec<_> = item.projects().collect();
+            if projects.is_empty() {
+                projects.push("");
+            }
+            for project in projects {
+                *statistics
+                    .open
+                    .entry(project.to_owned())
+                    .or_default()
+                    .entry(item.priority)
+                    .or_default() += 1;
+            }
+        }
+        statistics.priorities = priorities.iter().flatten().copied().map(Some).collect();
+        if priorities.contains(&None) {
+            statistics.priorities.push(None);
+        }
+
+        let completion_days: Vec<_> = items
+            .iter()
+            .filter_map(|item| {
+                item.completion_date
+                    .map(|date| (date - item.creation_date).num_days())
+            })
+            .collect();
+        statistics.completed_total = completion_days.len();
+        if !completion_days.is_empty() {
+            statistics.average_completion_days =
+                Some(completion_days.iter().sum::<i64>() as f64 / completion_days.len() as f64);
+        }
+        statistics.overdue_total = overdue_at(today) as usize;
+
+        statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::NaiveDate;
+
+    use super::Statistics;
+    use crate::todo::TodoItem;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn counts_completed_overdue_and_open_items() {
+        let items: Vec<TodoItem> = [
+            "x 2024-01-10 2024-01-05 Done today +work",
+            "x 2024-01-08 2024-01-07 Done on monday",
+            "(A) 2024-01-01 Late +work due:2024-01-09",
+            "2024-01-02 Open",
+        ]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect();
+
+        let statistics = Statistics::new(&items, date(2024, 1, 10), 3, 2);
+
+        assert_eq!(statistics.completed_days, [1, 0, 1]);
+        assert_eq!(statistics.overdue_days, [0, 0, 1]);
+        assert_eq!(statistics.week_starts, [date(2024, 1, 1), date(2024, 1, 8)]);
+        assert_eq!(statistics.completed_weeks, [0, 2]);
+        assert_eq!(statistics.overdue_weeks, [0, 1]);
+        assert_eq!(
+            statistics.open,
+            BTreeMap::from([
+                (String::new(), BTreeMap::from([(None, 1)])),
+                ("work".to_owned(), BTreeMap::from([(Some('A'), 1)])),
+            ])
+        );
+        assert_eq!(statistics.priorities, [Some('A'), None]);
+        assert_eq!(statistics.open_total, 2);
+        assert_eq!(statistics.completed_total, 2);
+        assert_eq!(statistics.overdue_total, 1);
+        assert_eq!(statistics.average_completion_days, Some(3.0));
+    }
+}
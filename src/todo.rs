@@ -1,10 +1,10 @@
-use chrono::NaiveDate;
+use chrono::{Days, Months, NaiveDate};
 use std::{
     fmt::Display,
     ops::{Deref, DerefMut},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TodoList {
     items: Vec<TodoItem>,
 }
@@ -41,18 +41,31 @@ pub struct TodoItem {
     pub rec: Option<Recurring>,
     pub due: Option<NaiveDate>,
     pub t: Option<NaiveDate>,
+    pub hidden: bool,
     content: Vec<ContentPart>,
     context_indices: Vec<usize>,
     project_indices: Vec<usize>,
+    source: Option<Source>,
 }
 
+/// The line an item was parsed from, written back unchanged as long as the item is
+#[derive(Debug, Clone)]
+struct Source {
+    text: String,
+    /// Serialization of the item right after parsing
+    serialized: String,
+}
+
+/// Writes the fields of an item in a normalized order
+struct Serialized<'a>(&'a TodoItem);
+
 #[derive(Debug, Clone)]
 pub struct ContentPart {
     pub space: String,
     pub content: Content,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Content {
     Word(String),
     Context(String),
@@ -76,17 +89,28 @@ pub enum RecurringUnit {
 
 impl Display for TodoItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(date) = self.completion_date {
+        let serialized = Serialized(self).to_string();
+        match &self.source {
+            Some(source) if source.serialized == serialized => f.write_str(&source.text),
+            _ => f.write_str(&serialized),
+        }
+    }
+}
+
+impl Display for Serialized<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Serialized(item) = self;
+        if let Some(date) = item.completion_date {
             write!(f, "x {date} ", date = date.format("%Y-%m-%d"))?;
-        } else if let Some(priority) = self.priority {
+        } else if let Some(priority) = item.priority {
             write!(f, "({priority}) ")?;
         }
 
-        write!(f, "{creation_date} ", creation_date = self.creation_date)?;
+        write!(f, "{creation_date} ", creation_date = item.creation_date)?;
 
-        if let Some(part) = self.content.first() {
+        if let Some(part) = item.content.first() {
             write!(f, "{content}", content = part.content)?;
-            for part in &self.content[1..] {
+            for part in &item.content[1..] {
                 write!(
                     f,
                     "{space}{content}",
@@ -96,24 +120,28 @@ impl Display for TodoItem {
             }
         };
 
-        if let Some(rec) = self.rec {
+        if let Some(rec) = item.rec {
             write!(f, " rec:{rec}")?;
         }
 
-        if let Some(due) = self.due {
+        if let Some(due) = item.due {
             write!(f, " due:{date}", date = due.format("%Y-%m-%d"))?;
         }
 
-        if let Some(t) = self.t {
+        if let Some(t) = item.t {
             write!(f, " t:{date}", date = t.format("%Y-%m-%d"))?;
         }
 
-        if let Some(prio) = self.priority {
-            if self.completion_date.is_some() {
+        if let Some(prio) = item.priority {
+            if item.completion_date.is_some() {
                 write!(f, " pri:{prio}")?;
             }
         }
 
+        if item.hidden {
+            write!(f, " h:1")?;
+        }
+
         Ok(())
     }
 }
@@ -156,9 +184,11 @@ impl TodoItem {
             rec: None,
             due: None,
             t: None,
+            hidden: false,
             content: vec![],
             context_indices: vec![],
             project_indices: vec![],
+            source: None,
         }
     }
 
@@ -198,6 +228,82 @@ impl TodoItem {
     pub fn content_parts(&self) -> impl Iterator<Item = &ContentPart> {
         self.content.iter()
     }
+
+    /// Appends the content at the end of the item
+    pub fn add_content(&mut self, content: Content) {
+        let space = if self.content.is_empty() { "" } else { " " };
+        self.content.push(ContentPart {
+            space: space.to_owned(),
+            content,
+        });
+        self.set_indices();
+    }
+
+    /// Replaces the first occurrence of `old` with `new`, appending `new` if `old` is missing
+    pub fn replace_content(&mut self, old: &Content, new: Content) {
+        match self.content.iter_mut().find(|part| &part.content == old) {
+            Some(part) => {
+                part.content = new;
+                self.set_indices();
+            }
+            None => self.add_content(new),
+        }
+    }
+
+    /// Removes all occurrences of the content
+    pub fn remove_content(&mut self, content: &Content) {
+        self.content.retain(|part| &part.content != content);
+        self.set_indices();
+    }
+
+    /// Returns the value of the first `key:value` word
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.content.iter().find_map(|part| match &part.content {
+            Content::Word(word) => word.strip_prefix(key)?.strip_prefix(':'),
+            _ => None,
+        })
+    }
+
+    /// Sets the value of the first `key:value` word, appending or removing it as necessary
+    pub fn set_tag(&mut self, key: &str, value: Option<&str>) {
+        let old = self
+            .tag(key)
+            .map(|old| Content::Word(format!("{key}:{old}")));
+        let new = value.map(|value| Content::Word(format!("{key}:{value}")));
+        match (old, new) {
+            (Some(old), Some(new)) => self.replace_content(&old, new),
+            (Some(old), None) => self.remove_content(&old),
+            (None, Some(new)) => self.add_content(new),
+            (None, None) => {}
+        }
+    }
+}
+
+impl Recurring {
+    /// Whether the next date is computed from the due date instead of the completion date
+    pub fn relative(&self) -> bool {
+        self.relative
+    }
+
+    pub fn amount(&self) -> u32 {
+        self.amount
+    }
+
+    pub fn unit(&self) -> RecurringUnit {
+        self.unit
+    }
+}
+
+impl RecurringUnit {
+    /// Adds the given amount of units to the date, returning `None` on overflow
+    pub fn add_to(self, date: NaiveDate, amount: u32) -> Option<NaiveDate> {
+        match self {
+            RecurringUnit::Days => date.checked_add_days(Days::new(amount.into())),
+            RecurringUnit::Weeks => date.checked_add_days(Days::new(u64::from(amount) * 7)),
+            RecurringUnit::Months => date.checked_add_months(Months::new(amount)),
+            RecurringUnit::Years => date.checked_add_months(Months::new(amount.checked_mul(12)?)),
+        }
+    }
 }
 
 impl Display for Content {
@@ -239,7 +345,7 @@ pub mod parsing {
 
     use crate::todo::{Content, ContentPart, Recurring};
 
-    use super::{RecurringUnit, TodoItem, TodoList};
+    use super::{RecurringUnit, Serialized, Source, TodoItem, TodoList};
 
     #[derive(Parser)]
     #[grammar = "./todo_grammar.pest"]
@@ -296,7 +402,12 @@ pub mod parsing {
                 Ok(mut pairs) => {
                     let item_pair = pairs.next().unwrap();
                     assert!(matches!(item_pair.as_rule(), Rule::item));
-                    Self::from_item_pair(item_pair)
+                    let mut item = Self::from_item_pair(item_pair)?;
+                    item.source = Some(Source {
+                        text: s.to_owned(),
+                        serialized: Serialized(&item).to_string(),
+                    });
+                    Ok(item)
                 }
                 Err(e) => match e.line_col {
                     LineColLocation::Pos((col_pos, _)) => Err(ItemParseError {
@@ -334,6 +445,7 @@ pub mod parsing {
             let mut due = None;
             let mut t = None;
             let mut rec = None;
+            let mut hidden = false;
             let mut content = vec![];
 
             fn parse_date(date: &str, span: pest::Span) -> Result<NaiveDate, ItemParseError> {
@@ -460,6 +572,17 @@ pub mod parsing {
                                     let t_date = parse_date(inner.as_str(), inner.as_span())?;
                                     t = Some(t_date);
                                 }
+                                Rule::hidden => {
+                                    if hidden {
+                                        return Err(ItemParseError {
+                                            error_message: "Illegal second 'h' definition"
+                                                .to_owned(),
+                                            error_span: span,
+                                        });
+                                    };
+
+                                    hidden = true;
+                                }
                                 _ => unreachable!(),
                             }
                         }
@@ -475,12 +598,58 @@ pub mod parsing {
                 rec,
                 due,
                 t,
+                hidden,
                 content,
                 context_indices: vec![],
                 project_indices: vec![],
+                source: None,
             };
             this.set_indices();
             Ok(this)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::TodoItem;
+
+    fn parse(line: &str) -> TodoItem {
+        line.parse().unwrap()
+    }
+
+    #[test]
+    fn hidden_requires_exact_value() {
+        assert!(parse("2024-01-01 Task h:1").hidden);
+        assert!(parse("2024-01-01 h:1 Task").hidden);
+        assert!(!parse("2024-01-01 Task h:10").hidden);
+        assert!(!parse("2024-01-01 Task h:1x").hidden);
+        assert!(!parse("2024-01-01 Task h:0").hidden);
+    }
+
+    #[test]
+    fn unchanged_items_keep_their_source_text() {
+        let line = "2024-01-01   Call  +work due:2024-02-01  @phone";
+        let mut item = parse(line);
+        assert_eq!(item.to_string(), line);
+
+        item.priority = Some('A');
+        assert_eq!(
+            item.to_string(),
+            "(A) 2024-01-01 Call  +work  @phone due:2024-02-01"
+        );
+        item.priority = None;
+        assert_eq!(item.to_string(), line);
+    }
+
+    #[test]
+    fn set_tag_replaces_appends_and_removes() {
+        let mut item = parse("2024-01-01 Task status:todo +work");
+        item.set_tag("status", Some("doing"));
+        assert_eq!(item.to_string(), "2024-01-01 Task status:doing +work");
+        item.set_tag("status", None);
+        assert_eq!(item.tag("status"), None);
+        item.set_tag("status", Some("done"));
+        assert_eq!(item.to_string(), "2024-01-01 Task +work status:done");
+    }
+}
@@ -2,7 +2,10 @@ use crate::app::App;
 use crate::event::EventHandler;
 use crate::ui;
 use ratatui::backend::Backend;
-use ratatui::crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use ratatui::crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+};
+use ratatui::crossterm::style::Print;
 use ratatui::crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::Terminal;
 use std::io;
@@ -29,9 +32,12 @@ impl<B: Backend> Tui<B> {
     /// Initializes the terminal interface.
     ///
     /// It enables the raw mode and sets terminal properties.
-    pub fn init(&mut self) -> std::io::Result<()> {
+    pub fn init(&mut self, mouse_capture: bool) -> std::io::Result<()> {
         terminal::enable_raw_mode()?;
-        ratatui::crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+        ratatui::crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableBracketedPaste)?;
+        if mouse_capture {
+            ratatui::crossterm::execute!(io::stderr(), EnableMouseCapture)?;
+        }
 
         // Define a custom panic hook to reset the terminal properties.
         // This way, you won't have your terminal messed up if an unexpected error happens.
@@ -55,13 +61,23 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 
+    /// Rings the terminal bell.
+    pub fn bell(&mut self) -> std::io::Result<()> {
+        ratatui::crossterm::execute!(io::stderr(), Print('\x07'))
+    }
+
     /// Resets the terminal interface.
     ///
     /// This function is also used for the panic hook to revert
     /// the terminal properties if unexpected errors occur.
     fn reset() -> std::io::Result<()> {
         terminal::disable_raw_mode()?;
-        ratatui::crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+        ratatui::crossterm::execute!(
+            io::stderr(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste
+        )?;
         Ok(())
     }
 
@@ -1,64 +1,735 @@
+use chrono::{Datelike, Months, NaiveDate, Weekday};
 use ratatui::{
     layout::{Constraint, Layout, Margin, Rect},
-    style::{Style, Stylize},
+    style::Style,
     text::{Line, Span, Text},
-    widgets::{Paragraph, Row, Table},
+    widgets::{Bar, BarChart, BarGroup, Block, Clear, Paragraph, Row, Sparkline, Table, Wrap},
     Frame,
 };
+use tui_input::Input;
 
 use crate::{
-    app::{App, FocusState, TodoListFilter},
-    config::Config,
+    app::{
+        parse_date_input, AgendaRow, App, Category, DueFilter, FocusState, PastedLine,
+        SortedFilteredTodoList, ViewRow,
+    },
+    config::{Config, DetailsPosition, WordLogic, WordMatch},
+    stats::Statistics,
     todo::{Content, TodoItem},
+    view::{DateKind, SortKey},
 };
 
+/// Width of a day in the calendar grid
+const CALENDAR_CELL_WIDTH: u16 = 5;
+
 /// Renders the user interface widgets.
 pub fn render(app: &mut App, frame: &mut Frame) {
     // This is where you add new widgets.
     // See the following resources:
+    let bar_height = if app.compact { 1 } else { 3 };
     let [top, mid, bot] = Layout::vertical([
-        Constraint::Length(3),
+        Constraint::Length(bar_height),
         Constraint::Min(1),
-        Constraint::Length(3),
+        Constraint::Length(bar_height),
     ])
     .areas(frame.size());
 
-    render_sortfilter(
+    let (completion_filter, priority_filter, t_filter) = render_sortfilter(frame, top, app);
+    app.click_areas.completion_filter = completion_filter;
+    app.click_areas.priority_filter = priority_filter;
+    app.click_areas.t_filter = t_filter;
+
+    let mid = if app.sidebar.visible {
+        let [sidebar_area, table_area] = Layout::horizontal([
+            Constraint::Length(app.config.sidebar_width()),
+            Constraint::Min(1),
+        ])
+        .areas(mid);
+        render_sidebar(
+            frame,
+            sidebar_area,
+            app,
+            matches!(app.state, FocusState::SidebarFocus),
+        );
+        table_area
+    } else {
+        mid
+    };
+
+    let mid = if app.calendar.visible {
+        let [calendar_area, table_area] = Layout::horizontal([
+            Constraint::Length(7 * CALENDAR_CELL_WIDTH + if app.compact { 0 } else { 2 }),
+            Constraint::Min(1),
+        ])
+        .areas(mid);
+        render_calendar(frame, calendar_area, app);
+        table_area
+    } else {
+        mid
+    };
+
+    render_status(frame, bot, app);
+
+    let mut screen_state = &app.state;
+    while let FocusState::HelpFocus { previous_state, .. }
+    | FocusState::ReminderFocus { previous_state, .. } = screen_state
+    {
+        screen_state = previous_state;
+    }
+    if matches!(
+        screen_state,
+        FocusState::AgendaFocus | FocusState::RescheduleFocus { .. }
+    ) {
+        render_agenda(frame, mid, app);
+        app.click_areas.list_rows.clear();
+        app.click_areas.list = Rect::default();
+    } else if let FocusState::StatsFocus { statistics, weekly } = screen_state {
+        render_stats(frame, mid, app, statistics, *weekly);
+        app.click_areas.list_rows.clear();
+        app.click_areas.list = Rect::default();
+    } else if matches!(screen_state, FocusState::BoardFocus) {
+        render_board(frame, mid, app);
+        app.click_areas.list_rows.clear();
+        app.click_areas.list = Rect::default();
+    } else {
+        let mid = if app.details_visible {
+            let size = app.config.details_size();
+            let (list_area, details_area) = match app.config.details_position() {
+                DetailsPosition::Right => {
+                    let size = match app.compact {
+                        true => size.min(mid.width / 2),
+                        false => size,
+                    };
+                    let [list_area, details_area] =
+                        Layout::horizontal([Constraint::Min(1), Constraint::Length(size)])
+                            .areas(mid);
+                    (list_area, details_area)
+                }
+                DetailsPosition::Bottom => {
+                    let size = match app.compact {
+                        true => size.min(mid.height / 2),
+                        false => size,
+                    };
+                    let [list_area, details_area] =
+                        Layout::vertical([Constraint::Min(1), Constraint::Length(size)]).areas(mid);
+                    (list_area, details_area)
+                }
+            };
+            render_details(frame, details_area, app);
+            list_area
+        } else {
+            mid
+        };
+
+        app.click_areas.list_rows = render_list(frame, mid, app);
+        app.click_areas.list = mid;
+    }
+
+    match &mut app.state {
+        FocusState::HelpFocus { scroll, .. } => {
+            render_help(frame, frame.size(), &app.config, scroll);
+        }
+        FocusState::PasteFocus { lines, scroll } => {
+            render_paste(frame, frame.size(), lines, &app.config, scroll);
+        }
+        FocusState::ReminderFocus {
+            reminders, scroll, ..
+        } => {
+            render_reminders(frame, frame.size(), reminders, &app.config, scroll);
+        }
+        _ => {}
+    }
+}
+
+/// Renders the key bindings as centered overlay, clamping the scroll offset to its content
+fn render_help(frame: &mut Frame, area: Rect, config: &Config, scroll: &mut u16) {
+    let sections = config.keys.help_sections();
+    let key_width = sections
+        .iter()
+        .flat_map(|(_, bindings)| bindings)
+        .map(|(key, _)| key.to_string().chars().count())
+        .max()
+        .unwrap_or_default();
+
+    let mut lines = Vec::new();
+    for (section, bindings) in &sections {
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::from(config.help_section(section)));
+        for (key, description) in bindings {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                config.help_key(*key, key_width),
+                Span::raw("  "),
+                Span::raw(*description),
+            ]));
+        }
+    }
+
+    render_popup(
         frame,
-        top,
-        app.todo_list.filter(),
-        &app.config,
-        matches!(app.state, FocusState::FilterFocus { .. }),
+        area,
+        config.help_width(),
+        config.help_title(),
+        lines,
+        config,
+        scroll,
     );
+}
 
-    const NUM_COLS: usize = 3;
-    const MIN_CONTENT_WIDTH: u16 = 20;
-    let table_widths: [Constraint; NUM_COLS] = [
-        Constraint::Length(app.config.completion_width() as u16),
-        Constraint::Length(app.config.priority_width() as u16),
-        Constraint::Min(MIN_CONTENT_WIDTH),
-    ];
+/// Renders the pasted lines as centered overlay, showing the parse error below invalid lines
+fn render_paste(
+    frame: &mut Frame,
+    area: Rect,
+    lines: &[PastedLine],
+    config: &Config,
+    scroll: &mut u16,
+) {
+    let valid = lines.iter().filter(|line| line.item.is_ok()).count();
+    let mut text = Vec::new();
+    for line in lines {
+        match &line.item {
+            Ok(item) => text.push(Line::raw(item.to_string())),
+            Err(error) => {
+                text.push(Line::from(config.paste_invalid(&line.text)));
+                text.push(Line::from(vec![Span::raw("  "), config.paste_error(error)]));
+            }
+        }
+    }
 
-    let content_width = Layout::horizontal(table_widths)
-        .spacing(1)
-        .areas::<NUM_COLS>(mid.inner(Margin::new(1, 0)))[2]
-        .width as usize;
-    let items = app.todo_list.items();
-    let rows = items.map(|item| render_item_row(item, content_width, &app.config));
-    let table = Table::new(rows, table_widths)
-        .block(app.config.default_block())
-        .highlight_style(app.config.item_selected_style())
-        .highlight_symbol(app.config.item_selection_mark());
-    frame.render_stateful_widget(table, mid, &mut *app.todo_list.table_state_mut())
+    render_popup(
+        frame,
+        area,
+        config.paste_width(),
+        config.paste_title(valid, lines.len() - valid),
+        text,
+        config,
+        scroll,
+    );
 }
 
-fn render_sortfilter(
+/// Renders the items that became due or reached their threshold date as centered overlay
+fn render_reminders(
     frame: &mut Frame,
     area: Rect,
-    filter: &TodoListFilter,
+    reminders: &[(TodoItem, DateKind)],
     config: &Config,
-    focused: bool,
+    scroll: &mut u16,
+) {
+    let kind_width = config.reminder_kind_width();
+    let lines = reminders
+        .iter()
+        .map(|(item, kind)| {
+            let mut spans = vec![config.reminder_kind(*kind, kind_width), Span::raw(" ")];
+            if let Some(priority) = item.priority {
+                spans.push(config.item_priority_mark(priority));
+                spans.push(Span::raw(" "));
+            }
+            for (i, part) in item.content_parts().enumerate() {
+                if i > 0 {
+                    spans.push(config.item_space(&part.space));
+                }
+                spans.push(match &part.content {
+                    Content::Word(word) => config.item_word(word),
+                    Content::Context(context) => config.item_context(context),
+                    Content::Project(project) => config.item_project(project),
+                });
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    render_popup(
+        frame,
+        area,
+        config.reminder_width(),
+        config.reminder_title(),
+        lines,
+        config,
+        scroll,
+    );
+}
+
+/// Renders the lines in a centered bordered overlay, clamping the scroll offset to its content
+fn render_popup(
+    frame: &mut Frame,
+    area: Rect,
+    width: u16,
+    title: Span,
+    lines: Vec<Line>,
+    config: &Config,
+    scroll: &mut u16,
 ) {
+    let line_count = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+    let width = width.min(area.width);
+    let height = line_count.saturating_add(2).min(area.height);
+    let area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    *scroll = (*scroll).min(line_count.saturating_sub(height.saturating_sub(2)));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(config.default_block().title(title))
+            .scroll((*scroll, 0)),
+        area,
+    );
+}
+
+/// Renders the list and returns the areas of the visible rows
+fn render_list(frame: &mut Frame, area: Rect, app: &App) -> Vec<(usize, Rect)> {
+    const MIN_CONTENT_WIDTH: u16 = 20;
+    const URGENCY_WIDTH: u16 = 5;
+    let config = &app.config;
+    let urgency_column = config.urgency_column();
+
+    let mut table_widths = mark_widths(config, app.compact);
+    if urgency_column {
+        table_widths.push(Constraint::Length(URGENCY_WIDTH));
+    }
+    table_widths.push(Constraint::Min(MIN_CONTENT_WIDTH));
+
+    let block = pane_block(app);
+    let inner = block.inner(area);
+    let content_width = Layout::horizontal(table_widths.clone())
+        .spacing(1)
+        .split(inner)
+        .last()
+        .unwrap()
+        .width as usize;
+    let num_cols = table_widths.len();
+    let search_words = app.todo_list.search_words();
+    let today = app.todo_list.today();
+    let list = app.todo_list.list();
+    let grouping = app.todo_list.grouping();
+    let (rows, heights): (Vec<_>, Vec<_>) = app
+        .todo_list
+        .rows()
+        .iter()
+        .map(|row| match row {
+            ViewRow::Header {
+                group,
+                count,
+                collapsed,
+            } => {
+                let mut cells = vec![Text::from(config.group_fold_mark(*collapsed))];
+                cells.resize(num_cols - 1, Text::default());
+                cells.push(Text::from(config.group_header(group, grouping, *count)));
+                (Row::new(cells), 1)
+            }
+            ViewRow::Item(index) => {
+                let item = &list[*index];
+                let mut cells = mark_cells(item, config, app.compact);
+                if urgency_column {
+                    let urgency = app.todo_list.urgency_of(item).value();
+                    cells.push(config.item_urgency(urgency).into());
+                }
+                let marked = app.todo_list.is_marked(*index);
+                render_item_row(
+                    item,
+                    cells,
+                    content_width,
+                    today,
+                    marked,
+                    &search_words,
+                    config,
+                )
+            }
+        })
+        .unzip();
+    let table = Table::new(rows, table_widths)
+        .block(block)
+        .highlight_style(config.item_selected_style())
+        .highlight_symbol(config.item_selection_mark());
+    let mut table_state = app.todo_list.table_state_mut();
+    if let Some(selected) = table_state.selected() {
+        *table_state.offset_mut() = padded_offset(
+            &heights,
+            selected,
+            table_state.offset(),
+            inner.height,
+            config.scroll_padding(),
+        );
+    }
+    frame.render_stateful_widget(table, area, &mut *table_state);
+
+    let mut y = inner.y;
+    let mut row_areas = vec![];
+    for (row, height) in heights.into_iter().enumerate().skip(table_state.offset()) {
+        if y >= inner.bottom() {
+            break;
+        }
+        let height = height.min(inner.bottom() - y);
+        row_areas.push((row, Rect::new(inner.x, y, inner.width, height)));
+        y += height;
+    }
+    row_areas
+}
+
+/// Renders the filtered items as cards in side by side columns
+fn render_board(frame: &mut Frame, area: Rect, app: &App) {
+    let config = &app.config;
+    let today = app.todo_list.today();
+    let list = app.todo_list.list();
+    let columns = &config.board.columns;
+    let cards = app.todo_list.board_cards(config.board.column_by, columns);
+    app.board.clamp_selections(&cards);
+
+    let areas = Layout::horizontal(vec![Constraint::Fill(1); columns.len()]).split(area);
+    for (column, (name, area)) in columns.iter().zip(areas.iter()).enumerate() {
+        let focused = column == app.board.column;
+        let mut table_widths = mark_widths(config, app.compact);
+        table_widths.push(Constraint::Min(1));
+        let mark_width = match focused {
+            true => config.item_selection_mark().width() as u16,
+            false => 0,
+        };
+        let block = pane_block(app).title(config.board_title(name, cards[column].len(), focused));
+        let content_width = Layout::horizontal(table_widths.clone())
+            .spacing(1)
+            .split(block.inner(*area))
+            .last()
+            .unwrap()
+            .width
+            .saturating_sub(mark_width) as usize;
+        let rows = cards[column].iter().map(|index| {
+            let item = &list[*index];
+            render_item_row(
+                item,
+                mark_cells(item, config, app.compact),
+                content_width,
+                today,
+                false,
+                &[],
+                config,
+            )
+            .0
+        });
+
+        let mut table = Table::new(rows, table_widths).block(block);
+        if focused {
+            table = table
+                .highlight_style(config.item_selected_style())
+                .highlight_symbol(config.item_selection_mark());
+        }
+        frame.render_stateful_widget(table, *area, &mut *app.board.column_state_mut(column));
+    }
+}
+
+/// Renders the open items of the coming days under day headings
+fn render_agenda(frame: &mut Frame, area: Rect, app: &App) {
+    let config = &app.config;
+    let today = app.todo_list.today();
+    let list = app.todo_list.list();
+    let rows = app
+        .todo_list
+        .agenda_rows(config.agenda.days, config.agenda.undated);
+    app.agenda.move_selection(&rows, 0);
+
+    let mut table_widths = mark_widths(config, app.compact);
+    let mark_columns = table_widths.len();
+    table_widths.push(Constraint::Min(20));
+    let block = pane_block(app);
+    let content_width = Layout::horizontal(table_widths.clone())
+        .spacing(1)
+        .split(block.inner(area))
+        .last()
+        .unwrap()
+        .width as usize;
+    let table_rows = rows.iter().map(|row| match row {
+        AgendaRow::Header(section) => {
+            let mut cells = vec![Text::default(); mark_columns];
+            cells.push(Text::from(config.agenda_header(*section, today)));
+            Row::new(cells)
+        }
+        AgendaRow::Item { index, .. } => {
+            let item = &list[*index];
+            render_item_row(
+                item,
+                mark_cells(item, config, app.compact),
+                content_width,
+                today,
+                false,
+                &[],
+                config,
+            )
+            .0
+        }
+    });
+
+    let table = Table::new(table_rows, table_widths)
+        .block(block)
+        .highlight_style(config.item_selected_style())
+        .highlight_symbol(config.item_selection_mark());
+    frame.render_stateful_widget(table, area, &mut *app.agenda.table_state_mut());
+}
+
+/// Renders the charts of completed and overdue items above the open items and a summary
+fn render_stats(frame: &mut Frame, area: Rect, app: &App, statistics: &Statistics, weekly: bool) {
+    const SUMMARY_WIDTH: u16 = 32;
+    const TOTAL_WIDTH: u16 = 5;
+    let config = &app.config;
+    let [completed_area, overdue_area, bottom] =
+        Layout::vertical([Constraint::Fill(1); 3]).areas(area);
+    let [open_area, summary_area] =
+        Layout::horizontal([Constraint::Min(1), Constraint::Length(SUMMARY_WIDTH)]).areas(bottom);
+
+    let (completed, overdue) = match weekly {
+        true => (&statistics.completed_weeks, &statistics.overdue_weeks),
+        false => (&statistics.completed_days, &statistics.overdue_days),
+    };
+    for (values, area, title, style) in [
+        (
+            completed,
+            completed_area,
+            config.stats_completed_title(weekly, completed.iter().copied().max().unwrap_or(0)),
+            config.stats_completed_style(),
+        ),
+        (
+            overdue,
+            overdue_area,
+            config.stats_overdue_title(weekly, overdue.iter().copied().max().unwrap_or(0)),
+            config.stats_overdue_style(),
+        ),
+    ] {
+        let block = pane_block(app).title(title);
+        if weekly {
+            let bars: Vec<_> = values
+                .iter()
+                .zip(&statistics.week_starts)
+                .map(|(value, start)| {
+                    Bar::default()
+                        .value(*value)
+                        .label(Line::raw(config.stats_week(*start)))
+                })
+                .collect();
+            let chart = BarChart::default()
+                .block(block)
+                .data(BarGroup::default().bars(&bars))
+                .bar_width(3)
+                .bar_style(style);
+            frame.render_widget(chart, area);
+        } else {
+            // Show the most recent days fitting into the chart
+            let width = block.inner(area).width as usize;
+            let values = &values[values.len().saturating_sub(width)..];
+            frame.render_widget(
+                Sparkline::default().block(block).data(values).style(style),
+                area,
+            );
+        }
+    }
+
+    let priority_width = config.priority_width().max(3) as u16;
+    let mut header = vec![Text::default()];
+    header.extend(
+        statistics
+            .priorities
+            .iter()
+            .map(|priority| Text::from(config.stats_priority(*priority)).right_aligned()),
+    );
+    header.push(Text::from(config.stats_total_header()).right_aligned());
+    let rows = statistics.open.iter().map(|(project, counts)| {
+        let mut cells = vec![Text::from(config.stats_project(project))];
+        cells.extend(statistics.priorities.iter().map(|priority| {
+            match counts.get(priority) {
+                Some(count) => Text::raw(count.to_string()),
+                None => Text::default(),
+            }
+            .right_aligned()
+        }));
+        cells.push(Text::raw(counts.values().sum::<usize>().to_string()).right_aligned());
+        Row::new(cells)
+    });
+    let mut widths = vec![Constraint::Min(1)];
+    widths.extend(vec![
+        Constraint::Length(priority_width);
+        statistics.priorities.len()
+    ]);
+    widths.push(Constraint::Length(TOTAL_WIDTH));
+    frame.render_widget(
+        Table::new(rows, widths)
+            .header(Row::new(header))
+            .block(pane_block(app).title(config.stats_open_title())),
+        open_area,
+    );
+
+    let field = |label, value: String| Line::from(vec![label, Span::raw(" "), Span::raw(value)]);
+    let lines = vec![
+        field(config.stats_open_label(), statistics.open_total.to_string()),
+        field(
+            config.stats_overdue_label(),
+            statistics.overdue_total.to_string(),
+        ),
+        field(
+            config.stats_completed_label(),
+            statistics.completed_total.to_string(),
+        ),
+        field(
+            config.stats_average_label(),
+            match statistics.average_completion_days {
+                Some(days) => format!("{days:.1}"),
+                None => "-".to_owned(),
+            },
+        ),
+    ];
+    frame.render_widget(
+        Paragraph::new(lines).block(pane_block(app).title(config.stats_summary_title())),
+        summary_area,
+    );
+}
+
+/// Adjusts the scroll offset so that `padding` rows around the selected row stay visible,
+/// as far as the viewport allows
+fn padded_offset(
+    heights: &[u16],
+    selected: usize,
+    offset: usize,
+    viewport: u16,
+    padding: usize,
+) -> usize {
+    let Some(last) = heights.len().checked_sub(1) else {
+        return 0;
+    };
+    let selected = selected.min(last);
+    let mut offset = offset.min(selected.saturating_sub(padding));
+    let bottom = (selected + padding).min(last);
+    let mut total: usize = heights[offset..=bottom]
+        .iter()
+        .copied()
+        .map(usize::from)
+        .sum();
+    while offset < selected && total > usize::from(viewport) {
+        total -= usize::from(heights[offset]);
+        offset += 1;
+    }
+    offset
+}
+
+fn render_details(frame: &mut Frame, area: Rect, app: &App) {
+    let config = &app.config;
+    let block = pane_block(app);
+    let Some(item) = app.todo_list.selected_item() else {
+        frame.render_widget(block, area);
+        return;
+    };
+
+    let field = |label, value| Line::from(vec![label, Span::raw(" "), value]);
+    let date = |date| Span::raw(config.format_date(date));
+    let today = app.todo_list.today();
+    let absolute = |date| match config.relative_dates() {
+        true => Span::raw(format!(" ({})", config.format_date(date))),
+        false => Span::raw(""),
+    };
+    let mut lines = vec![];
+
+    lines.push(field(
+        config.details_status_label(),
+        config.details_status(item.completion_date.is_some()),
+    ));
+    if let Some(priority) = item.priority {
+        lines.push(Line::from(vec![
+            config.details_priority_label(),
+            Span::raw(" "),
+            config.item_priority_mark(priority),
+        ]));
+    }
+    lines.push(field(
+        config.details_created_label(),
+        date(item.creation_date),
+    ));
+    if let Some(completion_date) = item.completion_date {
+        lines.push(field(
+            config.details_completed_label(),
+            date(completion_date),
+        ));
+    }
+    if let Some(due) = item.due {
+        lines.push(Line::from(vec![
+            config.details_due_label(),
+            Span::raw(" "),
+            config.item_due_date(due, today),
+            absolute(due),
+        ]));
+    }
+    if let Some(t) = item.t {
+        lines.push(Line::from(vec![
+            config.details_threshold_label(),
+            Span::raw(" "),
+            config.item_t_date(t, today),
+            absolute(t),
+        ]));
+    }
+    if let Some(rec) = item.rec {
+        lines.push(field(
+            config.details_recurrence_label(),
+            config.details_recurrence(rec),
+        ));
+    }
+    if item.hidden {
+        lines.push(field(
+            config.details_hidden_label(),
+            config.details_hidden_value(),
+        ));
+    }
+
+    let projects: Vec<_> = item.projects().collect();
+    if !projects.is_empty() {
+        lines.push(Line::from(config.details_projects_label()));
+        lines.extend(
+            projects
+                .into_iter()
+                .map(|project| Line::from(vec![Span::raw("  "), config.sidebar_project(project)])),
+        );
+    }
+    let contexts: Vec<_> = item.contexts().collect();
+    if !contexts.is_empty() {
+        lines.push(Line::from(config.details_contexts_label()));
+        lines.extend(
+            contexts
+                .into_iter()
+                .map(|context| Line::from(vec![Span::raw("  "), config.sidebar_context(context)])),
+        );
+    }
+
+    let urgency = app.todo_list.urgency_of(item);
+    lines.push(Line::from(vec![
+        config.details_urgency_label(),
+        Span::raw(" "),
+        config.item_urgency(urgency.value()),
+    ]));
+    lines.extend(urgency.terms.iter().map(|term| {
+        Line::raw(format!(
+            "  {source}: {factor:.2} * {coefficient:.1} = {value:.2}",
+            source = config.details_urgency_source(&term.source),
+            factor = term.factor,
+            coefficient = term.coefficient,
+            value = term.value(),
+        ))
+    }));
+
+    lines.push(Line::from(config.details_raw_label()));
+    lines.push(Line::raw(item.to_string()));
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block),
+        area,
+    );
+}
+
+/// Renders the filter bar, a single line without border and sort order in the compact layout,
+/// and returns the areas of the completion, priority and threshold filters
+fn render_sortfilter(frame: &mut Frame, area: Rect, app: &App) -> (Rect, Rect, Rect) {
+    let config = &app.config;
+    let filter = app.todo_list.filter();
+    let focused = matches!(app.state, FocusState::FilterFocus { .. });
     let completion = match filter.completion {
         Some(true) => config.item_complete_mark(),
         Some(false) => config.item_incomplete_mark(),
@@ -74,21 +745,64 @@ fn render_sortfilter(
     } else {
         config.filter_t_disabled()
     };
+    let hidden = if filter.hidden {
+        config.filter_hidden_enabled()
+    } else {
+        config.filter_hidden_disabled()
+    };
+    let due = match filter.due {
+        DueFilter::Any => config.filter_due_disabled(),
+        DueFilter::Overdue => config.filter_due_overdue(),
+        DueFilter::Today => config.filter_due_today(),
+        DueFilter::Within(days) => config.filter_due_within(days),
+        DueFilter::NoDue => config.filter_due_none(),
+    };
+    let word_logic = match filter.word_logic {
+        WordLogic::All => config.filter_words_all(),
+        WordLogic::Any => config.filter_words_any(),
+    };
+    let word_match = match filter.word_match {
+        WordMatch::Prefix => config.filter_match_prefix(),
+        WordMatch::Exact => config.filter_match_exact(),
+        WordMatch::Substring => config.filter_match_substring(),
+    };
     let input = filter.input_field.value();
-
-    frame.render_widget(config.default_block(), area);
-    let [completion_area, priority_area, t_area, input_area] = Layout::horizontal([
-        Constraint::Length(config.completion_width() as u16),
-        Constraint::Length(config.priority_width() as u16),
-        Constraint::Length(config.t_width() as u16),
-        Constraint::Min(10),
-    ])
-    .spacing(1)
-    .areas(area.inner(Margin::new(1, 1)));
+    let (sort, inner) = match app.compact {
+        true => (Line::default(), area),
+        false => {
+            frame.render_widget(config.default_block(), area);
+            (
+                Line::from(vec![
+                    config.sort_order(app.todo_list.sort()),
+                    config.grouping(app.todo_list.grouping()),
+                ]),
+                area.inner(Margin::new(1, 1)),
+            )
+        }
+    };
+    let [completion_area, priority_area, t_area, hidden_area, due_area, logic_area, match_area, input_area, sort_area] =
+        Layout::horizontal([
+            Constraint::Length(config.completion_width() as u16),
+            Constraint::Length(config.priority_width() as u16),
+            Constraint::Length(config.t_width() as u16),
+            Constraint::Length(config.hidden_width() as u16),
+            Constraint::Length(config.due_width() as u16),
+            Constraint::Length(config.word_logic_width() as u16),
+            Constraint::Length(config.word_match_width() as u16),
+            Constraint::Min(10),
+            Constraint::Length(sort.width() as u16),
+        ])
+        .spacing(1)
+        .areas(inner);
     frame.render_widget(Paragraph::new(completion), completion_area);
     frame.render_widget(Paragraph::new(priority), priority_area);
     frame.render_widget(Paragraph::new(t), t_area);
+    frame.render_widget(Paragraph::new(hidden), hidden_area);
+    frame.render_widget(Paragraph::new(due), due_area);
+    frame.render_widget(Paragraph::new(word_logic), logic_area);
+    frame.render_widget(Paragraph::new(word_match), match_area);
     frame.render_widget(Paragraph::new(input), input_area);
+    frame.render_widget(Paragraph::new(sort), sort_area);
 
     if focused {
         frame.set_cursor(
@@ -96,9 +810,276 @@ fn render_sortfilter(
             input_area.y,
         );
     }
+
+    (completion_area, priority_area, t_area)
 }
 
-fn render_item_row<'a>(item: &'a TodoItem, max_width: usize, config: &'a Config) -> Row<'a> {
+fn render_sidebar(frame: &mut Frame, area: Rect, app: &App, focused: bool) {
+    let config = &app.config;
+    let todo_list = &app.todo_list;
+    let counts = todo_list.category_counts();
+
+    let count_width = counts
+        .values()
+        .map(|count| config.sidebar_count(count.open, count.total).width())
+        .max()
+        .unwrap_or_default();
+    let rows = counts.iter().map(|(category, count)| {
+        let mark = if todo_list.filter().categories.contains(category) {
+            config.sidebar_selected_mark()
+        } else {
+            config.sidebar_unselected_mark()
+        };
+        let name = match category {
+            Category::Project(project) => config.sidebar_project(project),
+            Category::Context(context) => config.sidebar_context(context),
+        };
+        Row::new([
+            mark,
+            name,
+            config.sidebar_count(count.open, count.total),
+        ])
+    });
+
+    let mut table = Table::new(
+        rows,
+        [
+            Constraint::Length(config.sidebar_mark_width() as u16),
+            Constraint::Min(1),
+            Constraint::Length(count_width as u16),
+        ],
+    )
+    .block(pane_block(app));
+    if focused {
+        table = table
+            .highlight_style(config.item_selected_style())
+            .highlight_symbol(config.item_selection_mark());
+    }
+
+    let mut table_state = app.sidebar.table_state_mut();
+    if counts.is_empty() {
+        table_state.select(None);
+    } else {
+        let selected = table_state.selected().unwrap_or_default();
+        table_state.select(Some(selected.min(counts.len() - 1)));
+    }
+    frame.render_stateful_widget(table, area, &mut *table_state);
+}
+
+/// Renders the month grid around the calendar cursor with item counts below the days
+fn render_calendar<'a>(frame: &mut Frame, area: Rect, app: &'a App) {
+    let config = &app.config;
+    let selected = app.calendar.day;
+    let today = app.todo_list.today();
+    let first = selected.with_day(1).unwrap();
+    let last = first
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .unwrap();
+    let counts = app.todo_list.day_counts(first..=last);
+
+    let cell = |span: Span<'a>| {
+        let padding = (CALENDAR_CELL_WIDTH as usize).saturating_sub(span.width());
+        [Span::raw(" ".repeat(padding)), span]
+    };
+    let empty = || cell(Span::raw(""));
+
+    let mut lines = vec![Line::from_iter(
+        std::iter::successors(Some(Weekday::Mon), |weekday| Some(weekday.succ()))
+            .take(7)
+            .flat_map(|weekday| cell(config.calendar_weekday(weekday))),
+    )];
+    let offset = first.weekday().num_days_from_monday();
+    let mut day_line: Vec<_> = (0..offset).flat_map(|_| empty()).collect();
+    let mut count_line = day_line.clone();
+    for day in first.iter_days().take_while(|day| *day <= last) {
+        day_line.extend(cell(config.calendar_day(
+            day,
+            day == today,
+            day == selected,
+        )));
+        count_line.extend(match counts.get(&day) {
+            Some(count) => cell(config.calendar_count(*count)),
+            None => empty(),
+        });
+        if day.weekday() == Weekday::Sun || day == last {
+            lines.push(Line::from(std::mem::take(&mut day_line)));
+            lines.push(Line::from(std::mem::take(&mut count_line)));
+        }
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines).block(pane_block(app).title(config.calendar_month(first))),
+        area,
+    );
+}
+
+/// Fills the placeholders of the configured status bar format
+fn status_line<'a>(
+    config: &'a Config,
+    mode: &str,
+    file: &str,
+    todo_list: &SortedFilteredTodoList,
+    compact: bool,
+) -> Line<'a> {
+    let list = todo_list.list();
+    let completed = list
+        .iter()
+        .filter(|item| item.completion_date.is_some())
+        .count();
+    let mut spans = Vec::new();
+    let mut rest = config.status_format(compact);
+    while let Some((start, len)) = rest
+        .find('{')
+        .and_then(|start| Some((start, rest[start..].find('}')?)))
+    {
+        spans.push(Span::raw(&rest[..start]));
+        spans.push(match &rest[start + 1..start + len] {
+            "mode" => config.status_mode(mode),
+            "file" => Span::raw(file.to_owned()),
+            "dirty" => config.status_dirty(todo_list.is_modified()),
+            "visible" => Span::raw(todo_list.visible_count().to_string()),
+            "total" => Span::raw(list.len().to_string()),
+            "completed" => Span::raw(completed.to_string()),
+            "marked" => config.status_marked(todo_list.marks().len()),
+            _ => Span::raw(&rest[start..=start + len]),
+        });
+        rest = &rest[start + len + 1..];
+    }
+    spans.push(Span::raw(rest));
+
+    Line::from(spans).style(config.status_style())
+}
+
+/// Renders the status bar, with an active prompt or message on the left
+fn render_status(frame: &mut Frame, area: Rect, app: &App) {
+    let config = &app.config;
+    let status = status_line(
+        config,
+        app.state.name(),
+        &app.todo_path.to_string_lossy(),
+        &app.todo_list,
+        app.compact,
+    );
+
+    let inner = match app.compact {
+        true => area,
+        false => {
+            frame.render_widget(config.default_block(), area);
+            area.inner(Margin::new(1, 1))
+        }
+    };
+    let [left, right] = Layout::horizontal([
+        Constraint::Min(1),
+        Constraint::Length(status.width() as u16),
+    ])
+    .spacing(1)
+    .areas(inner);
+    frame.render_widget(status, right);
+
+    match &app.state {
+        FocusState::RescheduleFocus { input_field, .. } => {
+            let style = match parse_date_input(input_field.value(), app.todo_list.today()) {
+                Ok(_) => Style::new(),
+                Err(_) => config.input_error_style(),
+            };
+            render_prompt(
+                frame,
+                left,
+                config.reschedule_prompt(),
+                input_field,
+                style,
+                true,
+            );
+        }
+        FocusState::BatchFocus {
+            input_field,
+            prompt,
+            ..
+        } => {
+            let style = match prompt.parse(input_field.value()) {
+                Err(_) if !input_field.value().is_empty() => config.input_error_style(),
+                _ => Style::new(),
+            };
+            render_prompt(
+                frame,
+                left,
+                config.batch_prompt(*prompt),
+                input_field,
+                style,
+                true,
+            );
+        }
+        FocusState::SortFocus { input_field, .. } => {
+            let style = match SortKey::parse_order(input_field.value()) {
+                Ok(_) => Style::new(),
+                Err(_) => config.input_error_style(),
+            };
+            render_prompt(frame, left, config.sort_prompt(), input_field, style, true);
+        }
+        state => {
+            let search_focused = matches!(state, FocusState::SearchFocus { .. });
+            let search = app.todo_list.search();
+            if search_focused || !search.value().is_empty() {
+                render_prompt(
+                    frame,
+                    left,
+                    config.search_prompt(),
+                    search,
+                    Style::new(),
+                    search_focused,
+                );
+            } else if let Some(message) = &app.message {
+                frame.render_widget(config.status_message(&message.text, message.error), left);
+            }
+        }
+    }
+}
+
+fn render_prompt(
+    frame: &mut Frame,
+    area: Rect,
+    prompt: Span,
+    input: &Input,
+    style: Style,
+    focused: bool,
+) {
+    let prompt_width = prompt.width() as u16;
+
+    frame.render_widget(
+        Line::from(vec![prompt, Span::styled(input.value(), style)]),
+        area,
+    );
+
+    if focused {
+        frame.set_cursor(area.x + prompt_width + input.visual_cursor() as u16, area.y);
+    }
+}
+
+/// Returns the block around the panes, only keeping the top border in the compact layout
+fn pane_block(app: &App) -> Block<'_> {
+    match app.compact {
+        true => app.config.compact_block(),
+        false => app.config.default_block(),
+    }
+}
+
+/// Returns the widths of the completion and priority columns, merged in the compact layout
+fn mark_widths(config: &Config, compact: bool) -> Vec<Constraint> {
+    match compact {
+        true => vec![Constraint::Length(
+            config.completion_width().max(config.priority_width()) as u16,
+        )],
+        false => vec![
+            Constraint::Length(config.completion_width() as u16),
+            Constraint::Length(config.priority_width() as u16),
+        ],
+    }
+}
+
+/// Returns the completion and priority cells of the item, merged in the compact layout
+/// where open items show their priority instead of the incomplete mark
+fn mark_cells<'a>(item: &TodoItem, config: &'a Config, compact: bool) -> Vec<Text<'a>> {
     let completion = if item.completion_date.is_some() {
         config.item_complete_mark()
     } else {
@@ -110,16 +1091,41 @@ fn render_item_row<'a>(item: &'a TodoItem, max_width: usize, config: &'a Config)
         None => config.item_no_priority_mark(),
     };
 
+    match (compact, item.completion_date, item.priority) {
+        (true, None, Some(_)) => vec![priority.into()],
+        (true, _, _) => vec![completion.into()],
+        (false, _, _) => vec![completion.into(), priority.into()],
+    }
+}
+
+/// Renders the item as table row after the given leading cells, wrapping its content at the width
+fn render_item_row<'a>(
+    item: &'a TodoItem,
+    mut cells: Vec<Text<'a>>,
+    max_width: usize,
+    today: NaiveDate,
+    marked: bool,
+    search_words: &[String],
+    config: &'a Config,
+) -> (Row<'a>, u16) {
     let mut spans = vec![];
     let mut line_width = 0;
     let mut lines = vec![];
     let mut first = true;
 
     for part in item.content_parts() {
-        let span = match &part.content {
-            Content::Word(word) => config.item_word(word),
-            Content::Context(context) => config.item_context(context),
-            Content::Project(project) => config.item_project(project),
+        let (span, text) = match &part.content {
+            Content::Word(word) => (config.item_word(word), word),
+            Content::Context(context) => (config.item_context(context), context),
+            Content::Project(project) => (config.item_project(project), project),
+        };
+        let span = if !search_words.is_empty() && {
+            let text = text.to_lowercase();
+            search_words.iter().any(|word| text.contains(word))
+        } {
+            span.patch_style(config.search_match_style())
+        } else {
+            span
         };
         let space = if first {
             first = false;
@@ -141,8 +1147,8 @@ fn render_item_row<'a>(item: &'a TodoItem, max_width: usize, config: &'a Config)
         }
     }
 
-    let t = item.t.map(|date| config.item_t_date(date));
-    let due = item.due.map(|date| config.item_due_date(date));
+    let t = item.t.map(|date| config.item_t_date(date, today));
+    let due = item.due.map(|date| config.item_due_date(date, today));
     for span in [t, due].into_iter().flatten() {
         line_width += span.width() + 1;
 
@@ -163,5 +1169,31 @@ fn render_item_row<'a>(item: &'a TodoItem, max_width: usize, config: &'a Config)
     let content = Text::from_iter(lines);
     let height = content.height() as u16;
 
-    Row::new([completion.into(), priority.into(), content]).height(height)
+    cells.push(content);
+    (
+        Row::new(cells).height(height).style(match marked {
+            true => config
+                .item_row_style(item, today)
+                .patch(config.item_marked_style()),
+            false => config.item_row_style(item, today),
+        }),
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::padded_offset;
+
+    #[test]
+    fn padded_offset_keeps_rows_around_the_selection_visible() {
+        let rows = [1; 10];
+        assert_eq!(padded_offset(&rows, 0, 0, 5, 2), 0);
+        assert_eq!(padded_offset(&rows, 6, 0, 5, 2), 4);
+        assert_eq!(padded_offset(&rows, 3, 4, 5, 2), 1);
+        assert_eq!(padded_offset(&rows, 9, 0, 5, 2), 5);
+        assert_eq!(padded_offset(&rows, 5, 0, 3, 10), 5);
+        assert_eq!(padded_offset(&[1, 3, 3, 1], 2, 0, 4, 1), 2);
+        assert_eq!(padded_offset(&[], 3, 2, 5, 2), 0);
+    }
 }
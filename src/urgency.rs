@@ -0,0 +1,180 @@
+use chrono::NaiveDate;
+
+use crate::{config::UrgencyCoefficients, todo::TodoItem};
+
+/// Urgency of an item, summed up from weighted terms
+#[derive(Debug, Clone, Default)]
+pub struct Urgency {
+    /// The terms contributing to the urgency
+    pub terms: Vec<UrgencyTerm>,
+}
+
+/// A single contribution to an [`Urgency`]
+#[derive(Debug, Clone)]
+pub struct UrgencyTerm {
+    /// What contributed
+    pub source: UrgencySource,
+    /// How much the source applies, usually between 0 and 1
+    pub factor: f64,
+    /// The configured weight of the source
+    pub coefficient: f64,
+}
+
+/// The item properties contributing to an [`Urgency`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrgencySource {
+    /// Proximity of the due date, given as days until due (negative when overdue)
+    Due(i64),
+    /// The priority letter
+    Priority(char),
+    /// Days since creation
+    Age(i64),
+    /// Threshold date not yet reached
+    Threshold,
+    Project(String),
+    Context(String),
+}
+
+impl Urgency {
+    /// Computes the urgency of an item
+    ///
+    /// Completed items are never urgent.
+    pub fn of(item: &TodoItem, today: NaiveDate, coefficients: &UrgencyCoefficients) -> Self {
+        let mut terms = vec![];
+        if item.completion_date.is_some() {
+            return Self { terms };
+        }
+
+        if let Some(due) = item.due {
+            let days = (due - today).num_days();
+            // Overdue by a week or more counts fully, due in two weeks or later counts 0.2,
+            // with a linear transition in between
+            let factor = match days {
+                ..=-7 => 1.0,
+                -6..=13 => 1.0 - (days + 7) as f64 * 0.8 / 21.0,
+                _ => 0.2,
+            };
+            terms.push(UrgencyTerm {
+                source: UrgencySource::Due(days),
+                factor,
+                coefficient: coefficients.due,
+            });
+        }
+
+        if let Some(priority) = item.priority {
+            if let Some(coefficient) = coefficients.priorities.get(&priority) {
+                terms.push(UrgencyTerm {
+                    source: UrgencySource::Priority(priority),
+                    factor: 1.0,
+                    coefficient: *coefficient,
+                });
+            }
+        }
+
+        let age = (today - item.creation_date).num_days().max(0);
+        if coefficients.age_max_days > 0 {
+            terms.push(UrgencyTerm {
+                source: UrgencySource::Age(age),
+                factor: (age as f64 / coefficients.age_max_days as f64).min(1.0),
+                coefficient: coefficients.age,
+            });
+        }
+
+        if item.t.is_some_and(|t| today < t) {
+            terms.push(UrgencyTerm {
+                source: UrgencySource::Threshold,
+                factor: 1.0,
+                coefficient: coefficients.threshold,
+            });
+        }
+
+        for project in item.projects() {
+            if let Some(coefficient) = coefficients.projects.get(project) {
+                terms.push(UrgencyTerm {
+                    source: UrgencySource::Project(project.to_owned()),
+                    factor: 1.0,
+                    coefficient: *coefficient,
+                });
+            }
+        }
+
+        for context in item.contexts() {
+            if let Some(coefficient) = coefficients.contexts.get(context) {
+                terms.push(UrgencyTerm {
+                    source: UrgencySource::Context(context.to_owned()),
+                    factor: 1.0,
+                    coefficient: *coefficient,
+                });
+            }
+        }
+
+        Self { terms }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.terms.iter().fold(0.0, |sum, term| sum + term.value())
+    }
+}
+
+impl UrgencyTerm {
+    pub fn value(&self) -> f64 {
+        self.factor * self.coefficient
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{Urgency, UrgencySource};
+    use crate::config::UrgencyCoefficients;
+
+    fn urgency(line: &str, coefficients: &UrgencyCoefficients) -> Urgency {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+        Urgency::of(&line.parse().unwrap(), today, coefficients)
+    }
+
+    #[test]
+    fn terms_of_open_items() {
+        let mut coefficients = UrgencyCoefficients::default();
+        coefficients.projects.insert("work".to_owned(), 1.5);
+        let urgency = urgency(
+            "(A) 2024-01-01 Report +work @desk due:2024-01-10 t:2024-01-20",
+            &coefficients,
+        );
+
+        let sources: Vec<_> = urgency.terms.iter().map(|term| &term.source).collect();
+        assert_eq!(
+            sources,
+            [
+                &UrgencySource::Due(7),
+                &UrgencySource::Priority('A'),
+                &UrgencySource::Age(2),
+                &UrgencySource::Threshold,
+                &UrgencySource::Project("work".to_owned()),
+            ]
+        );
+        let expected = 12.0 * (1.0 - 14.0 * 0.8 / 21.0) + 6.0 + 2.0 * 2.0 / 365.0 - 5.0 + 1.5;
+        assert!((urgency.value() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn due_factor_is_clamped_and_completed_items_are_not_urgent() {
+        let coefficients = UrgencyCoefficients {
+            age_max_days: 0,
+            ..UrgencyCoefficients::default()
+        };
+        assert_eq!(
+            urgency("2024-01-01 Late due:2023-12-01", &coefficients).value(),
+            12.0
+        );
+        assert!(
+            (urgency("2024-01-01 Far due:2024-06-01", &coefficients).value() - 2.4).abs() < 1e-9
+        );
+        assert!(
+            urgency("x 2024-01-02 2024-01-01 Done due:2023-12-01", &coefficients)
+                .terms
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,539 @@
+use std::{cmp::Ordering, collections::BTreeSet, fmt::Display, str::FromStr};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::todo::{Content, TodoItem};
+
+/// Date field of an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateKind {
+    Due,
+    Threshold,
+}
+
+/// Section of the agenda
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgendaSection {
+    /// Open items with a past due date
+    Overdue,
+    /// Items due or reaching their threshold on a day
+    Day(NaiveDate),
+    /// Open items without due and threshold date
+    Undated,
+}
+
+/// Amount of items due or reaching their threshold on a day
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DayCount {
+    /// Items due on the day
+    pub due: usize,
+    /// Items with the threshold date on the day
+    pub t: usize,
+}
+
+/// Prompt asking for a value applied to all targeted items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchPrompt {
+    /// Priority letter, or nothing to remove the priority
+    SetPriority,
+    /// Projects and contexts like `+project @context` to add
+    AddCategories,
+    /// Projects and contexts like `+project @context` to remove
+    RemoveCategories,
+}
+
+/// Change of an item parsed from a [`BatchPrompt`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemEdit {
+    SetPriority(Option<char>),
+    Add(Vec<Content>),
+    Remove(Vec<Content>),
+}
+
+/// What the list view is grouped by
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Grouping {
+    /// A flat list without group headers
+    #[default]
+    None,
+    Project,
+    Context,
+    Priority,
+    Due,
+}
+
+/// What the columns of the board are defined by
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoardColumns {
+    /// Priority letter, an empty column value collects items without priority
+    Priority,
+    /// Context name, an empty column value collects items without context
+    Context,
+    /// Value of the `status:` tag, an empty column value collects items without tag
+    #[default]
+    Status,
+}
+
+/// A group of the list view, ordered as displayed
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Group {
+    Project(String),
+    Context(String),
+    Priority(char),
+    Due(DueBucket),
+    /// Items lacking the grouped by field
+    Without,
+}
+
+/// Due dates relative to today, as used for grouping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DueBucket {
+    Overdue,
+    Today,
+    /// Due within the next seven days
+    ThisWeek,
+    Later,
+}
+
+/// A single key of a sort order
+///
+/// Written as the field name, optionally followed by `-desc`, e.g. `created-desc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+/// The item fields a [`SortKey`] can sort by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    /// Incomplete items first
+    Completion,
+    /// Highest priority first, items without priority last
+    Priority,
+    /// Oldest creation date first
+    Created,
+    /// Oldest completion date first, incomplete items last
+    Completed,
+    /// Earliest due date first, items without due date last
+    Due,
+    /// Earliest threshold date first, items without threshold last
+    Threshold,
+    /// Alphabetically by first project, items without project last
+    Project,
+    /// Alphabetically by first context, items without context last
+    Context,
+    /// Alphabetically by content
+    Text,
+    /// By position in the underlying list, i.e. the order in the file
+    File,
+    /// Least urgent first
+    Urgency,
+}
+
+/// An item as compared by a [`SortKey`]
+#[derive(Debug, Clone, Copy)]
+pub struct SortItem<'a> {
+    /// Index into the underlying list
+    pub index: usize,
+    pub item: &'a TodoItem,
+    pub urgency: f64,
+}
+
+impl BatchPrompt {
+    /// Parses the user input into the edit to apply
+    pub fn parse(self, input: &str) -> Result<ItemEdit, String> {
+        match self {
+            BatchPrompt::SetPriority => {
+                let mut chars = input.trim().chars();
+                match (chars.next(), chars.next()) {
+                    (None, _) => Ok(ItemEdit::SetPriority(None)),
+                    (Some(c), None) if c.is_ascii_alphabetic() => {
+                        Ok(ItemEdit::SetPriority(Some(c.to_ascii_uppercase())))
+                    }
+                    _ => Err(format!("Invalid priority '{input}'")),
+                }
+            }
+            BatchPrompt::AddCategories | BatchPrompt::RemoveCategories => {
+                let categories = input
+                    .split_whitespace()
+                    .map(|word| {
+                        let mut chars = word.chars();
+                        let first = chars.next();
+                        let name = chars.as_str();
+                        match first {
+                            Some('+') if !name.is_empty() => Ok(Content::Project(name.to_owned())),
+                            Some('@') if !name.is_empty() => Ok(Content::Context(name.to_owned())),
+                            _ => Err(format!("Invalid project or context '{word}'")),
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if categories.is_empty() {
+                    return Err("No project or context given".to_owned());
+                }
+                Ok(match self {
+                    BatchPrompt::AddCategories => ItemEdit::Add(categories),
+                    _ => ItemEdit::Remove(categories),
+                })
+            }
+        }
+    }
+}
+
+impl ItemEdit {
+    pub fn apply(&self, item: &mut TodoItem) {
+        match self {
+            ItemEdit::SetPriority(priority) => item.priority = *priority,
+            ItemEdit::Add(categories) => {
+                for category in categories {
+                    if !item.content_parts().any(|part| &part.content == category) {
+                        item.add_content(category.clone());
+                    }
+                }
+            }
+            ItemEdit::Remove(categories) => {
+                for category in categories {
+                    item.remove_content(category);
+                }
+            }
+        }
+    }
+}
+
+impl SortField {
+    pub fn ascending(self) -> SortKey {
+        SortKey {
+            field: self,
+            descending: false,
+        }
+    }
+
+    pub fn descending(self) -> SortKey {
+        SortKey {
+            field: self,
+            descending: true,
+        }
+    }
+}
+
+impl SortKey {
+    /// Compares two items by this key
+    pub fn compare(&self, a: SortItem, b: SortItem) -> Ordering {
+        /// Keeps `None` last regardless of the direction
+        fn none_last<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+            match (a, b) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) if descending => b.cmp(&a),
+                (Some(a), Some(b)) => a.cmp(&b),
+            }
+        }
+
+        fn directed(ordering: Ordering, descending: bool) -> Ordering {
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        }
+
+        fn text(item: &TodoItem) -> Vec<String> {
+            item.content_parts()
+                .map(|part| part.content.to_string().to_lowercase())
+                .collect()
+        }
+
+        let descending = self.descending;
+        let (a_index, a_urgency, a) = (a.index, a.urgency, a.item);
+        let (b_index, b_urgency, b) = (b.index, b.urgency, b.item);
+        match self.field {
+            SortField::Completion => directed(
+                a.completion_date
+                    .is_some()
+                    .cmp(&b.completion_date.is_some()),
+                descending,
+            ),
+            SortField::Priority => none_last(a.priority, b.priority, descending),
+            SortField::Created => directed(a.creation_date.cmp(&b.creation_date), descending),
+            SortField::Completed => none_last(a.completion_date, b.completion_date, descending),
+            SortField::Due => none_last(a.due, b.due, descending),
+            SortField::Threshold => none_last(a.t, b.t, descending),
+            SortField::Project => none_last(a.projects().next(), b.projects().next(), descending),
+            SortField::Context => none_last(a.contexts().next(), b.contexts().next(), descending),
+            SortField::Text => directed(text(a).cmp(&text(b)), descending),
+            SortField::File => directed(a_index.cmp(&b_index), descending),
+            SortField::Urgency => directed(a_urgency.total_cmp(&b_urgency), descending),
+        }
+    }
+
+    /// Parses a sort order of keys separated by commas or whitespace
+    pub fn parse_order(s: &str) -> Result<Vec<SortKey>, String> {
+        s.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|key| !key.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Formats a sort order, so it can be parsed by [`SortKey::parse_order`]
+    pub fn format_order(order: &[SortKey]) -> String {
+        order
+            .iter()
+            .map(SortKey::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self.field {
+            SortField::Completion => "completion",
+            SortField::Priority => "priority",
+            SortField::Created => "created",
+            SortField::Completed => "completed",
+            SortField::Due => "due",
+            SortField::Threshold => "t",
+            SortField::Project => "project",
+            SortField::Context => "context",
+            SortField::Text => "text",
+            SortField::File => "file",
+            SortField::Urgency => "urgency",
+        })?;
+
+        if self.descending {
+            f.write_str("-desc")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, descending) = match s.strip_suffix("-desc") {
+            Some(name) => (name, true),
+            None => (s, false),
+        };
+        let field = match name {
+            "completion" => SortField::Completion,
+            "priority" => SortField::Priority,
+            "created" => SortField::Created,
+            "completed" => SortField::Completed,
+            "due" => SortField::Due,
+            "t" => SortField::Threshold,
+            "project" => SortField::Project,
+            "context" => SortField::Context,
+            "text" => SortField::Text,
+            "file" => SortField::File,
+            "urgency" => SortField::Urgency,
+            _ => return Err(format!("Invalid sort key '{s}'")),
+        };
+
+        Ok(Self { field, descending })
+    }
+}
+
+impl TryFrom<String> for SortKey {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<SortKey> for String {
+    fn from(value: SortKey) -> Self {
+        value.to_string()
+    }
+}
+
+impl Grouping {
+    pub fn next(self) -> Self {
+        match self {
+            Grouping::None => Grouping::Project,
+            Grouping::Project => Grouping::Context,
+            Grouping::Context => Grouping::Priority,
+            Grouping::Priority => Grouping::Due,
+            Grouping::Due => Grouping::None,
+        }
+    }
+
+    /// Returns the groups an item belongs to, which may be several projects or contexts
+    pub fn groups_of(self, item: &TodoItem, today: NaiveDate) -> BTreeSet<Group> {
+        let mut groups: BTreeSet<_> = match self {
+            Grouping::None => BTreeSet::new(),
+            Grouping::Project => item
+                .projects()
+                .map(|p| Group::Project(p.to_owned()))
+                .collect(),
+            Grouping::Context => item
+                .contexts()
+                .map(|c| Group::Context(c.to_owned()))
+                .collect(),
+            Grouping::Priority => item.priority.map(Group::Priority).into_iter().collect(),
+            Grouping::Due => item
+                .due
+                .map(|due| Group::Due(DueBucket::of(due, today)))
+                .into_iter()
+                .collect(),
+        };
+
+        if groups.is_empty() {
+            groups.insert(Group::Without);
+        }
+        groups
+    }
+}
+
+impl DueBucket {
+    pub fn of(due: NaiveDate, today: NaiveDate) -> Self {
+        match (due - today).num_days() {
+            ..=-1 => DueBucket::Overdue,
+            0 => DueBucket::Today,
+            1..=7 => DueBucket::ThisWeek,
+            _ => DueBucket::Later,
+        }
+    }
+}
+
+impl BoardColumns {
+    /// Key of the tag used by [`BoardColumns::Status`]
+    pub const STATUS_TAG: &'static str = "status";
+
+    /// Returns the index of the column the item belongs to, defaulting to the first one
+    pub fn column_of(self, item: &TodoItem, columns: &[String]) -> usize {
+        columns
+            .iter()
+            .position(|column| self.matches(item, column))
+            .unwrap_or_default()
+    }
+
+    fn matches(self, item: &TodoItem, column: &str) -> bool {
+        match self {
+            BoardColumns::Priority => item.priority == column.chars().next(),
+            BoardColumns::Context if column.is_empty() => item.contexts().next().is_none(),
+            BoardColumns::Context => item.contexts().any(|context| context == column),
+            BoardColumns::Status => item.tag(Self::STATUS_TAG).unwrap_or_default() == column,
+        }
+    }
+
+    /// Rewrites the token of the item matching column `from` to match column `to`
+    pub fn move_item(self, item: &mut TodoItem, from: &str, to: &str) {
+        match self {
+            BoardColumns::Priority => item.priority = to.chars().next(),
+            BoardColumns::Context => {
+                let old = Content::Context(from.to_owned());
+                let new = Content::Context(to.to_owned());
+                match (from.is_empty(), to.is_empty()) {
+                    (true, true) => {}
+                    (true, false) => item.add_content(new),
+                    (false, true) => item.remove_content(&old),
+                    (false, false) => item.replace_content(&old, new),
+                }
+            }
+            BoardColumns::Status => {
+                item.set_tag(Self::STATUS_TAG, Some(to).filter(|to| !to.is_empty()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{BatchPrompt, BoardColumns, ItemEdit, SortField, SortItem, SortKey};
+    use crate::todo::{Content, TodoItem};
+
+    #[test]
+    fn batch_categories_accept_non_ascii_words() {
+        assert_eq!(
+            BatchPrompt::AddCategories.parse("+über @café"),
+            Ok(ItemEdit::Add(vec![
+                Content::Project("über".to_owned()),
+                Content::Context("café".to_owned()),
+            ]))
+        );
+        for input in ["über", "ö", "🎉", "+", "@"] {
+            assert!(
+                BatchPrompt::RemoveCategories.parse(input).is_err(),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn sort_order_parses_and_formats() {
+        let order = SortKey::parse_order("created-desc, t,file").unwrap();
+        assert_eq!(
+            order,
+            [
+                SortField::Created.descending(),
+                SortField::Threshold.ascending(),
+                SortField::File.ascending(),
+            ]
+        );
+        assert_eq!(SortKey::format_order(&order), "created-desc,t,file");
+        for input in ["bogus", "due-asc", "-desc"] {
+            assert!(input.parse::<SortKey>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn sort_keys_compare_with_missing_values_last() {
+        let a: TodoItem = "(A) 2024-01-01 Alpha due:2024-01-05".parse().unwrap();
+        let b: TodoItem = "2024-01-02 Beta due:2024-01-03".parse().unwrap();
+        let a = SortItem {
+            index: 0,
+            item: &a,
+            urgency: 2.0,
+        };
+        let b = SortItem {
+            index: 1,
+            item: &b,
+            urgency: 1.0,
+        };
+        let compare = |key: SortKey| key.compare(a, b);
+
+        assert_eq!(compare(SortField::Priority.ascending()), Ordering::Less);
+        assert_eq!(compare(SortField::Priority.descending()), Ordering::Less);
+        assert_eq!(compare(SortField::Due.ascending()), Ordering::Greater);
+        assert_eq!(compare(SortField::Due.descending()), Ordering::Less);
+        assert_eq!(compare(SortField::Text.ascending()), Ordering::Less);
+        assert_eq!(compare(SortField::File.descending()), Ordering::Greater);
+        assert_eq!(compare(SortField::Urgency.ascending()), Ordering::Greater);
+        assert_eq!(compare(SortField::Completion.ascending()), Ordering::Equal);
+    }
+
+    #[test]
+    fn board_columns_move_items_between_columns() {
+        let columns = ["".to_owned(), "A".to_owned(), "B".to_owned()];
+        let mut item: TodoItem = "(A) 2024-01-01 Task @home".parse().unwrap();
+        assert_eq!(BoardColumns::Priority.column_of(&item, &columns), 1);
+        BoardColumns::Priority.move_item(&mut item, "A", "B");
+        assert_eq!(item.priority, Some('B'));
+        BoardColumns::Priority.move_item(&mut item, "B", "");
+        assert_eq!(BoardColumns::Priority.column_of(&item, &columns), 0);
+
+        BoardColumns::Context.move_item(&mut item, "home", "office");
+        assert_eq!(item.contexts().collect::<Vec<_>>(), ["office"]);
+        BoardColumns::Context.move_item(&mut item, "office", "");
+        assert_eq!(item.contexts().next(), None);
+        BoardColumns::Context.move_item(&mut item, "", "home");
+        assert_eq!(item.contexts().collect::<Vec<_>>(), ["home"]);
+
+        let columns = ["".to_owned(), "doing".to_owned()];
+        BoardColumns::Status.move_item(&mut item, "", "doing");
+        assert_eq!(item.tag(BoardColumns::STATUS_TAG), Some("doing"));
+        assert_eq!(BoardColumns::Status.column_of(&item, &columns), 1);
+        BoardColumns::Status.move_item(&mut item, "doing", "");
+        assert_eq!(item.tag(BoardColumns::STATUS_TAG), None);
+    }
+}